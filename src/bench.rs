@@ -0,0 +1,158 @@
+use std::io;
+
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+
+use crate::logic::{solve, Puzzle, Solution, SolveConfig, Strategy};
+
+/// Seeds used by `--bench` when none are given.
+pub const DEFAULT_SEEDS: std::ops::Range<u64> = 0..10;
+
+/// One strategy run on the board drawn from one seed.
+pub struct BenchRow {
+    pub seed: u64,
+    pub size: usize,
+    pub strategy: Strategy,
+    pub expanded: usize,
+    pub path_len: usize,
+    pub millis: u128,
+}
+
+/// Counters of one solve, as `--stats` prints them for CI to compare.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StatsReport {
+    pub expanded: usize,
+    pub generated: usize,
+    pub max_open: usize,
+    pub path_len: usize,
+    pub optimal: bool,
+    /// Left out of deterministic reports, the only field that changes from
+    /// run to run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub millis: Option<u128>,
+}
+
+impl StatsReport {
+    pub fn new(solution: &Solution, deterministic: bool) -> Self {
+        StatsReport {
+            expanded: solution.stats.expanded,
+            generated: solution.stats.generated,
+            max_open: solution.stats.max_open,
+            path_len: solution.moves.len(),
+            optimal: solution.optimal,
+            millis: match deterministic {
+                true => None,
+                false => Some(solution.stats.elapsed.as_millis()),
+            },
+        }
+    }
+
+    /// One line of JSON.
+    pub fn write_json(&self, mut out: impl io::Write) -> io::Result<()> {
+        serde_json::to_writer(&mut out, self)?;
+        writeln!(out)
+    }
+}
+
+/// The first board drawn from `seed` that can reach `goal`, the same one
+/// every time for the same seed.
+pub fn board_from_seed(seed: u64, goal: &Puzzle) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    loop {
+        let board = Puzzle::from_rng(&mut rng);
+        if board.is_solvable_to(goal) {
+            break board;
+        }
+    }
+}
+
+/// Runs every strategy on the board of every seed.
+pub fn run(seeds: impl IntoIterator<Item = u64>, goal: Puzzle) -> Vec<BenchRow> {
+    let mut rows = Vec::new();
+
+    for seed in seeds {
+        let initial = board_from_seed(seed, &goal);
+        for strategy in Strategy::all() {
+            let solution =
+                solve(initial, goal, &SolveConfig::new(strategy)).expect("solvable board");
+            rows.push(BenchRow {
+                seed,
+                size: Puzzle::SIDE,
+                strategy,
+                expanded: solution.stats.expanded,
+                path_len: solution.moves.len(),
+                millis: solution.stats.elapsed.as_millis(),
+            });
+        }
+    }
+
+    rows
+}
+
+pub fn write_csv(rows: &[BenchRow], mut out: impl io::Write) -> io::Result<()> {
+    writeln!(out, "seed,size,heuristic,expanded,path_len,millis")?;
+    for row in rows {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            row.seed,
+            row.size,
+            row.strategy.name(),
+            row.expanded,
+            row.path_len,
+            row.millis
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, Value};
+
+    use super::*;
+
+    /// The JSON line `report` writes, as an object.
+    fn written(report: &StatsReport) -> Map<String, Value> {
+        let mut out = Vec::new();
+        report.write_json(&mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.lines().count(), 1);
+        match serde_json::from_str(&line).unwrap() {
+            Value::Object(fields) => fields,
+            other => panic!("not an object: {}", other),
+        }
+    }
+
+    fn solution() -> Solution {
+        let goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        solve(initial, goal, &SolveConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn deterministic_report_leaves_out_millis() {
+        let solution = solution();
+        let fields = written(&StatsReport::new(&solution, true));
+
+        let expected = serde_json::json!({
+            "expanded": solution.stats.expanded,
+            "generated": solution.stats.generated,
+            "max_open": solution.stats.max_open,
+            "path_len": solution.moves.len(),
+            "optimal": solution.optimal,
+        });
+        assert_eq!(Value::Object(fields), expected);
+    }
+
+    #[test]
+    fn timed_report_includes_millis() {
+        let solution = solution();
+        let fields = written(&StatsReport::new(&solution, false));
+
+        let millis = solution.stats.elapsed.as_millis() as u64;
+        assert_eq!(fields.get("millis"), Some(&Value::from(millis)));
+        assert_eq!(fields.len(), 6);
+    }
+}
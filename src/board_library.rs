@@ -0,0 +1,94 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::logic::Puzzle;
+
+pub const LIBRARY_PATH: &str = "boards.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub name: String,
+    pub initial: Puzzle,
+    pub goal: Puzzle,
+}
+
+/// Named (initial, goal) pairs kept in a JSON file between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BoardLibrary {
+    pub entries: Vec<LibraryEntry>,
+}
+
+impl BoardLibrary {
+    /// Reads the library at `path`, a missing file is an empty library.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BoardLibrary::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LibraryEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Adds `entry`, replacing any entry with the same name.
+    pub fn insert(&mut self, entry: LibraryEntry) {
+        match self.entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(old) => *old = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, initial: [[u8; 3]; 3]) -> LibraryEntry {
+        LibraryEntry {
+            name: name.to_string(),
+            initial: Puzzle::new(initial),
+            goal: Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]),
+        }
+    }
+
+    #[test]
+    fn insert_replaces_an_entry_of_the_same_name() {
+        let mut library = BoardLibrary::default();
+        library.insert(entry("easy", [[2, 8, 3], [1, 6, 4], [7, 0, 5]]));
+        library.insert(entry("hard", [[5, 6, 7], [4, 0, 8], [3, 2, 1]]));
+        let replacement = entry("easy", [[1, 2, 3], [8, 4, 0], [7, 6, 5]]);
+        library.insert(replacement.clone());
+
+        assert_eq!(library.names().collect::<Vec<_>>(), ["easy", "hard"]);
+        let easy = library.get("easy").unwrap();
+        assert_eq!(
+            (easy.initial, easy.goal),
+            (replacement.initial, replacement.goal)
+        );
+    }
+
+    #[test]
+    fn missing_file_is_an_empty_library() {
+        let path = std::env::temp_dir().join(format!("nine-{}-missing.json", std::process::id()));
+        assert!(!path.exists());
+
+        let library = BoardLibrary::load(&path).unwrap();
+        assert!(library.entries.is_empty());
+        assert!(!library.contains("easy"));
+    }
+}
@@ -1,19 +1,89 @@
 use std::{
     cell::{Cell, RefCell},
-    collections::{hash_map, HashMap},
+    collections::{hash_map, BTreeMap, HashMap, HashSet},
     iter::Map,
     ops::Deref,
     rc::Rc,
 };
 
+use nine::logic::Puzzle;
 use raylib::{color::Color, prelude::*};
 
 use crate::{
-    logic::Puzzle,
-    ui::elements::{draw_small_puzzle, SmallPuzzleCenter},
+    ui::{
+        elements::{
+            draw_small_puzzle, g_ramp_color, range_ramp_color, SmallPuzzleCenter, SOURCE_COLORS,
+        },
+        theme::Theme,
+    },
     AsMapSearchTree, MapSearchTree,
 };
 
+/// f = g + h of every board of a searched tree, with h estimated anew as
+/// the search did, to color the boards by. Boards sharing an f the heuristic
+/// cannot tell apart show up as clusters of one color: the plateaus a weak
+/// heuristic spends its expansions on. The weights of a weighted search are
+/// left out, so f is plain g + h.
+pub struct FValues {
+    f: HashMap<Puzzle, i32>,
+    range: (i32, i32),
+}
+
+impl FValues {
+    pub fn new(map: &HashMap<Puzzle, (Puzzle, i32)>, mut h: impl FnMut(&Puzzle) -> i32) -> Self {
+        let f = map
+            .iter()
+            .map(|(puzzle, &(_, g))| (*puzzle, g + h(puzzle)))
+            .collect::<HashMap<_, _>>();
+        let range = (
+            f.values().copied().min().unwrap_or(0),
+            f.values().copied().max().unwrap_or(0),
+        );
+        FValues { f, range }
+    }
+
+    /// Lowest and highest f in the tree.
+    pub fn range(&self) -> (i32, i32) {
+        self.range
+    }
+
+    fn color(&self, puzzle: &Puzzle) -> Option<Color> {
+        let f = *self.f.get(puzzle)?;
+        Some(range_ramp_color(f, self.range))
+    }
+}
+
+/// Which part of a combined heuristic gives the estimate of every board of a
+/// searched tree, to color the boards by. The border between the two colors
+/// is where one part takes over from the other.
+pub struct HeuristicSources {
+    source: HashMap<Puzzle, usize>,
+    names: [&'static str; 2],
+}
+
+impl HeuristicSources {
+    pub fn new(
+        map: &HashMap<Puzzle, (Puzzle, i32)>,
+        names: [&'static str; 2],
+        mut source: impl FnMut(&Puzzle) -> usize,
+    ) -> Self {
+        let source = map.keys().map(|puzzle| (*puzzle, source(puzzle))).collect();
+        HeuristicSources { source, names }
+    }
+
+    /// Names of the parts, by source.
+    pub fn names(&self) -> [&'static str; 2] {
+        self.names
+    }
+
+    fn color(&self, puzzle: &Puzzle) -> Option<Color> {
+        SOURCE_COLORS.get(*self.source.get(puzzle)?).copied()
+    }
+}
+
+/// Width of the depth labels `draw_depth_axis` puts down the left edge.
+const DEPTH_AXIS_WIDTH: i32 = 24;
+
 pub struct IntRectBound {
     pub left: i32,
     pub top: i32,
@@ -21,6 +91,37 @@ pub struct IntRectBound {
     pub bottom: i32,
 }
 
+/// Which end of the solution path the drawn tree hangs from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeRoot {
+    /// The search tree as explored, rooted at the initial board.
+    Initial,
+    /// The same tree re-rooted at the path end (the goal once solved), so
+    /// the highlighted path leads back to the initial board.
+    Goal,
+}
+
+/// How the boards of an animated tree are placed as the search adds them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeLayout {
+    /// Every parent centered over its children. Placed boards shift
+    /// sideways as their subtrees grow.
+    Tidy,
+    /// Boards keep the place they were first drawn at, new ones go under
+    /// their parent or to the right of their level. Less tidy, but nothing
+    /// moves.
+    Stable,
+}
+
+/// A move of the keyboard selection through the drawn tree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeStep {
+    Parent,
+    FirstChild,
+    PreviousSibling,
+    NextSibling,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Visibility {
     Full,
@@ -29,7 +130,9 @@ enum Visibility {
 }
 
 pub struct DrawTreeNode {
-    puzzle: Puzzle,
+    pub puzzle: Puzzle,
+    pub parent: Puzzle,
+    pub g: i32,
     pub depth: u32,
     children: Vec<RcRefDrawTreeNode>,
     pub center_x: i32,
@@ -40,6 +143,12 @@ pub struct DrawTreeNode {
     on_path: Cell<bool>,
     coord_built_for: Cell<Option<PuzzleSizer>>,
     is_open_set: bool,
+    /// Animation frame the board was first drawn in, 0 outside of
+    /// animations.
+    added_at: usize,
+    /// Column the board keeps in a stable layout, counted in board widths
+    /// from the root. `None` in a tidy one.
+    column: Option<i32>,
 }
 
 #[derive(Clone)]
@@ -54,9 +163,11 @@ impl Deref for RcRefDrawTreeNode {
 }
 
 impl DrawTreeNode {
-    fn new_rc_ref(puzzle: Puzzle, is_open_set: bool) -> RcRefDrawTreeNode {
+    fn new_rc_ref(puzzle: Puzzle, parent: Puzzle, g: i32, is_open_set: bool) -> RcRefDrawTreeNode {
         RcRefDrawTreeNode(Rc::new(RefCell::new(DrawTreeNode {
             puzzle,
+            parent,
+            g,
             depth: 0,
             children: Vec::new(),
             center_x: 0,
@@ -67,8 +178,19 @@ impl DrawTreeNode {
             on_path: Cell::new(false),
             coord_built_for: Cell::new(None),
             is_open_set,
+            added_at: 0,
+            column: None,
         })))
     }
+
+    pub fn children(&self) -> &[RcRefDrawTreeNode] {
+        &self.children
+    }
+
+    /// Whether the node lies on the path between the root and the path end.
+    pub fn on_path(&self) -> bool {
+        self.on_path.get()
+    }
 }
 
 impl RcRefDrawTreeNode {
@@ -95,15 +217,41 @@ impl RcRefDrawTreeNode {
     }
 
     pub fn build_coord(&self, sizer: &PuzzleSizer) {
-        if self.borrow().coord_built_for.get() == Some(*sizer) {
+        let built_for = self.borrow().coord_built_for.get();
+        if built_for.is_some_and(|built_for| built_for.same_layout(sizer)) {
             return;
         }
 
-        self.build_width_phase1(sizer);
-        self.build_width_phase2(sizer);
+        let stable = self.borrow().column.is_some();
+        if stable {
+            self.build_stable_coord(sizer);
+        } else {
+            self.build_width_phase1(sizer);
+            self.build_width_phase2(sizer);
+        }
         self.borrow().coord_built_for.set(Some(*sizer));
     }
 
+    fn build_stable_coord(&self, sizer: &PuzzleSizer) {
+        // Columns are as far apart as two neighbouring leaves of a tidy
+        // layout, the spans cover the whole subtree for culling.
+        let half = sizer.puzzle_center_offset();
+        let pitch = 2 * half + 1 + sizer.puzzle_cell();
+        let center_x = self.borrow().column.unwrap_or(0) * pitch;
+        let (mut min_x, mut max_x) = (center_x - half, center_x + half);
+
+        for child in self.borrow().children.iter() {
+            child.build_stable_coord(sizer);
+            min_x = min_x.min(child.borrow().min_x);
+            max_x = max_x.max(child.borrow().max_x);
+        }
+
+        let mut inner = self.borrow_mut();
+        inner.center_x = center_x;
+        inner.min_x = min_x;
+        inner.max_x = max_x;
+    }
+
     fn build_width_phase1(&self, sizer: &PuzzleSizer) {
         // The leaf nodes makes their min_x = -4 and max_x = 4
         // The parent nodes accumulate their children's min_x and max_x
@@ -120,9 +268,11 @@ impl RcRefDrawTreeNode {
                 width += child.borrow().max_x - child.borrow().min_x + 1;
             }
 
+            // An even width cannot be split evenly around the center, the
+            // spare pixel goes to the right so the span stays `width` wide.
             let mut inner = self.borrow_mut();
             inner.min_x = -(width - 1) / 2;
-            inner.max_x = (width - 1) / 2; // TODO
+            inner.max_x = inner.min_x + width - 1;
         }
     }
 
@@ -134,12 +284,13 @@ impl RcRefDrawTreeNode {
 
         for child in self.borrow().children.iter() {
             {
+                // The child's min_x and max_x are still relative to its own
+                // center here, shift them so its span starts at `left`.
                 let mut child = child.borrow_mut();
-                child.center_x = left + (child.max_x - child.min_x) / 2;
+                child.center_x = left - child.min_x;
 
-                let new_max = child.center_x + (child.max_x - child.min_x) / 2;
-                let new_min = child.center_x - (child.max_x - child.min_x) / 2;
-                child.min_x = new_min;
+                let new_max = left + child.max_x - child.min_x;
+                child.min_x = left;
                 child.max_x = new_max;
 
                 left = new_max + sizer.puzzle_cell() + 1;
@@ -171,11 +322,51 @@ impl RcRefDrawTreeNode {
         }
     }
 
-    pub fn draw(&self, painter: &mut ElementPainter) {
+    /// Draws the tree, returning how many edges were left out to stay
+    /// within `painter.max_edges`.
+    pub fn draw(&self, painter: &mut ElementPainter) -> usize {
         self.build_coord(&painter.sizer);
         self.update_visibility(&painter.bound, painter.offset);
         self.draw_phase_2(painter, false);
-        self.draw_phase_3(painter);
+        let omitted = self.draw_phase_3(painter);
+
+        if painter.ghosts {
+            let mut hidden = BTreeMap::new();
+            self.count_hidden(&painter.bound, &mut hidden);
+            for ((depth, right), count) in hidden {
+                painter.draw_ghost(depth, right, count);
+            }
+        }
+        omitted
+    }
+
+    /// Adds up the boards off to the side of `bound`, by the level the
+    /// hidden subtrees start at and whether they are off to the right.
+    fn count_hidden(&self, bound: &IntRectBound, hidden: &mut BTreeMap<(u32, bool), usize>) {
+        let inner = self.borrow();
+        match inner.visibility.get() {
+            Visibility::Full => {}
+            Visibility::Partial => {
+                for child in inner.children.iter() {
+                    child.count_hidden(bound, hidden);
+                }
+            }
+            Visibility::None => {
+                let right = inner.draw_x.get() > bound.right;
+                *hidden.entry((inner.depth, right)).or_default() += self.subtree_len();
+            }
+        }
+    }
+
+    /// Number of boards in the tree under this node, itself included.
+    fn subtree_len(&self) -> usize {
+        let children = self
+            .borrow()
+            .children
+            .iter()
+            .map(Self::subtree_len)
+            .sum::<usize>();
+        children + 1
     }
 
     pub fn draw_phase_2(&self, painter: &mut ElementPainter, fully_visible: bool) {
@@ -191,7 +382,7 @@ impl RcRefDrawTreeNode {
         if let Some(y) = painter.get_draw_y(inner.depth) {
             let x = painter.get_draw_x(center_x);
 
-            painter.draw_small_puzzle(&inner.puzzle, x, y, inner.on_path.get(), inner.is_open_set);
+            painter.draw_small_puzzle(&inner, x, y);
 
             inner.draw_x.set(x);
             inner.visibility.set(match fully_visible {
@@ -200,6 +391,10 @@ impl RcRefDrawTreeNode {
             });
 
             if !inner.children.is_empty() {
+                if painter.is_truncated(inner.depth) {
+                    painter.draw_truncation_marker(x, y);
+                }
+
                 for child in inner.children.iter() {
                     child.draw_phase_2(painter, fully_visible);
                 }
@@ -207,48 +402,178 @@ impl RcRefDrawTreeNode {
         }
     }
 
-    fn draw_phase_3(&self, painter: &mut ElementPainter) {
-        if self.borrow().visibility.get() == Visibility::None {
+    /// Draws the edges of the drawn part of the tree, at most
+    /// `painter.max_edges` of them, and returns how many were left out.
+    /// Families on the solution path come first, then those fully inside the
+    /// bound before those running off its side, shallower ones first.
+    fn draw_phase_3(&self, painter: &mut ElementPainter) -> usize {
+        let mut parents = Vec::new();
+        self.collect_edge_parents(painter, &mut parents);
+        parents.sort_by_key(|parent| {
+            let parent = parent.borrow();
+            let partial = parent.visibility.get() != Visibility::Full;
+            (!parent.on_path.get(), partial, parent.depth)
+        });
+
+        let mut budget = painter.max_edges.unwrap_or(usize::MAX);
+        let mut omitted = 0;
+        let mut drawn = Vec::new();
+        for parent in parents {
+            let edges = parent.drawn_children(painter).len();
+            if edges > budget {
+                omitted += edges;
+                continue;
+            }
+            budget -= edges;
+            drawn.push(parent);
+        }
+
+        // The path last, so that it stays on top.
+        for parent in drawn.iter().rev() {
+            parent.draw_family_edges(painter);
+        }
+        omitted
+    }
+
+    /// Drawn nodes under this one whose edges to their children are drawn
+    /// too.
+    fn collect_edge_parents(&self, painter: &ElementPainter, parents: &mut Vec<RcRefDrawTreeNode>) {
+        let inner = self.borrow();
+        if inner.visibility.get() == Visibility::None || painter.get_draw_y(inner.depth).is_none() {
             return;
         }
 
+        if !inner.children.is_empty() && !painter.is_truncated(inner.depth) {
+            parents.push(self.clone());
+            for child in inner.children.iter() {
+                child.collect_edge_parents(painter, parents);
+            }
+        }
+    }
+
+    /// Children drawn by `draw_phase_2`, each at the end of one edge.
+    fn drawn_children(&self, painter: &ElementPainter) -> Vec<RcRefDrawTreeNode> {
+        let inner = self.borrow();
+        let drawn = inner.children.iter().filter(|child| {
+            let child = child.borrow();
+            child.visibility.get() != Visibility::None && painter.get_draw_y(child.depth).is_some()
+        });
+        drawn.cloned().collect()
+    }
+
+    /// The edges from this node down to each of its drawn children.
+    fn draw_family_edges(&self, painter: &mut ElementPainter) {
         let inner = self.borrow();
+        let Some(y) = painter.get_draw_y(inner.depth) else {
+            return;
+        };
+        let x = inner.draw_x.get();
         let on_path = inner.on_path.get();
 
-        if let Some(y) = painter.get_draw_y(inner.depth) {
-            let x = inner.draw_x.get();
+        // The lines shared by all children take the cheapest one's color,
+        // siblings have the same g with unit move costs.
+        let children_g = inner.children.iter().map(|child| child.borrow().g).min();
+        let children_g = children_g.unwrap_or(inner.g);
+        painter.draw_line_down(x, y, on_path, children_g);
 
-            if inner.depth != 0 {
-                painter.draw_line_up(x, y, on_path);
-            }
+        // A stable layout does not keep the parent between its children, nor
+        // the children in the order they are drawn.
+        let children_x = inner
+            .children
+            .iter()
+            .map(|child| child.borrow().draw_x.get());
+        let left_x = children_x.clone().fold(x, i32::min);
+        let right_x = children_x.fold(x, i32::max);
 
-            if !inner.children.is_empty() {
-                painter.draw_line_down(x, y, on_path);
+        painter.draw_line_across(left_x, right_x, y, children_g);
 
-                let left_x = inner.children.first().unwrap().borrow().draw_x.get();
-                let right_x = inner.children.last().unwrap().borrow().draw_x.get();
+        for child in self.drawn_children(painter) {
+            let child = child.borrow();
+            let child_y = painter.get_draw_y(child.depth).unwrap();
+            painter.draw_line_up(child.draw_x.get(), child_y, child.on_path.get(), child.g);
+        }
 
-                painter.draw_line_across(left_x, right_x, y);
+        let path_child = inner
+            .children
+            .iter()
+            .find(|child| child.borrow().on_path.get());
+        if let (true, Some(path_child)) = (on_path, path_child) {
+            let other_end = path_child.borrow().draw_x.get();
+            painter.draw_line_across_on_path(x, y, other_end);
+        }
+    }
 
-                let mut child_on_path_id = -1;
+    /// The node holding `puzzle` in the tree under this one.
+    pub fn find(&self, puzzle: &Puzzle) -> Option<RcRefDrawTreeNode> {
+        if self.borrow().puzzle == *puzzle {
+            return Some(self.clone());
+        }
+        self.borrow()
+            .children
+            .iter()
+            .find_map(|child| child.find(puzzle))
+    }
 
-                for (child, id) in inner.children.iter().zip(0..) {
-                    child.draw_phase_3(painter);
-                    if child.borrow().on_path.get() {
-                        child_on_path_id = id as i32;
-                    }
-                }
+    /// The node whose children hold `node`, in the tree under this one.
+    fn parent_of(&self, node: &RcRefDrawTreeNode) -> Option<RcRefDrawTreeNode> {
+        let inner = self.borrow();
+        if inner.children.iter().any(|child| Rc::ptr_eq(child, node)) {
+            return Some(self.clone());
+        }
+        inner
+            .children
+            .iter()
+            .find_map(|child| child.parent_of(node))
+    }
 
-                if on_path && child_on_path_id >= 0 {
-                    let other_end = inner.children[child_on_path_id as usize]
-                        .borrow()
-                        .draw_x
-                        .get();
+    /// Where `step` takes the selection from `selected`, in the tree rooted
+    /// at this node. `None` when there is nothing in that direction.
+    pub fn step_from(
+        &self,
+        selected: &RcRefDrawTreeNode,
+        step: TreeStep,
+    ) -> Option<RcRefDrawTreeNode> {
+        if step == TreeStep::FirstChild {
+            return selected.borrow().children.first().cloned();
+        }
 
-                    painter.draw_line_across_on_path(x, y, other_end);
-                }
-            }
+        let parent = self.parent_of(selected)?;
+        if step == TreeStep::Parent {
+            return Some(parent);
+        }
+
+        let parent = parent.borrow();
+        let index = parent
+            .children
+            .iter()
+            .position(|child| Rc::ptr_eq(child, selected))?;
+        let sibling = match step {
+            TreeStep::PreviousSibling => index.checked_sub(1)?,
+            _ => index + 1,
+        };
+        parent.children.get(sibling).cloned()
+    }
+
+    /// The drawn node under the screen position `(x, y)`, as laid out by the
+    /// last `draw` with the same painter.
+    pub fn node_at(&self, painter: &ElementPainter, x: i32, y: i32) -> Option<RcRefDrawTreeNode> {
+        let inner = self.borrow();
+        if inner.visibility.get() == Visibility::None {
+            return None;
+        }
+
+        let node_y = painter.get_draw_y(inner.depth)?;
+        let node_x = inner.draw_x.get();
+        let half = painter.puzzle_center_offset() + 1;
+
+        if (x - node_x).abs() <= half && (y - node_y).abs() <= half {
+            return Some(self.clone());
         }
+
+        inner
+            .children
+            .iter()
+            .find_map(|child| child.node_at(painter, x, y))
     }
 
     pub fn new_from_map_search_tree<
@@ -258,30 +583,220 @@ impl RcRefDrawTreeNode {
         tree: &'a impl IterableSearchTree<'a, T>,
         path_end: &Puzzle,
     ) -> (RcRefDrawTreeNode, Option<RcRefDrawTreeNode>) {
-        let root_node = DrawTreeNode::new_rc_ref(*tree.initial(), false);
-        let mut temp_nodes = HashMap::new();
+        Self::new_rooted_from_map_search_tree(tree, path_end, TreeRoot::Initial)
+    }
 
-        temp_nodes.insert(tree.initial(), root_node.clone());
+    pub fn new_rooted_from_map_search_tree<
+        'a,
+        T: Iterator<Item = (&'a Puzzle, &'a (Puzzle, i32), bool)>,
+    >(
+        tree: &'a impl IterableSearchTree<'a, T>,
+        path_end: &Puzzle,
+        root: TreeRoot,
+    ) -> (RcRefDrawTreeNode, Option<RcRefDrawTreeNode>) {
+        let mut edges = Vec::new();
+        let mut parents = HashMap::new();
+        let mut search_info = HashMap::new();
 
-        for (puzzle, (parent, _), is_open_set) in tree.iter() {
+        for (puzzle, (parent, g), is_open_set) in tree.iter() {
+            search_info.insert(puzzle, (*parent, *g, is_open_set));
             if puzzle == parent {
                 continue;
             }
+            parents.insert(puzzle, parent);
+            edges.push((puzzle, parent));
+        }
+        // `tree` may list its boards in any order, a hash map's changes from
+        // run to run. Children are pushed in edge order, sorting the edges
+        // keeps siblings, and with them the drawn and exported tree, the
+        // same every time.
+        edges.sort_by_key(|(puzzle, _)| puzzle.packed());
+
+        // Re-rooting at the path end only flips the edges along the path
+        // between the two ends, every other subtree keeps its parent.
+        let (root_puzzle, path_end) = match root {
+            TreeRoot::Goal if parents.contains_key(path_end) => {
+                let mut path = HashSet::new();
+                let mut current = path_end;
+                while let Some(&parent) = parents.get(current) {
+                    path.insert(current);
+                    current = parent;
+                }
+
+                for (puzzle, parent) in edges.iter_mut() {
+                    if path.contains(puzzle) {
+                        std::mem::swap(puzzle, parent);
+                    }
+                }
+
+                (path_end, tree.initial())
+            }
+            _ => (tree.initial(), path_end),
+        };
+
+        let new_node = |puzzle: &Puzzle| {
+            let (parent, g, is_open_set) = search_info
+                .get(puzzle)
+                .copied()
+                .unwrap_or((*puzzle, 0, false));
+            DrawTreeNode::new_rc_ref(*puzzle, parent, g, is_open_set)
+        };
+        let root_node = new_node(root_puzzle);
+        let mut temp_nodes = HashMap::new();
+
+        temp_nodes.insert(root_puzzle, root_node.clone());
+
+        for (puzzle, parent) in edges {
             let puzzle_node = temp_nodes
                 .entry(puzzle)
-                .or_insert_with(|| DrawTreeNode::new_rc_ref(*puzzle, is_open_set))
+                .or_insert_with(|| new_node(puzzle))
                 .clone();
             temp_nodes
                 .entry(parent)
-                .or_insert_with(|| DrawTreeNode::new_rc_ref(*parent, is_open_set))
+                .or_insert_with(|| new_node(parent))
                 .borrow_mut()
                 .children
                 .push(puzzle_node.clone());
         }
 
-        let (_, goal_node) = root_node.build_depth(0, path_end);
+        let (_, path_end_node) = root_node.build_depth(0, path_end);
 
-        (root_node, goal_node)
+        (root_node, path_end_node)
+    }
+}
+
+/// Draw tree of a running search, updated one board at a time instead of
+/// rebuilt from the whole search map on every animation frame. Children keep
+/// the order they were found in.
+pub struct IncrementalDrawTree {
+    root: RcRefDrawTreeNode,
+    nodes: HashMap<Puzzle, RcRefDrawTreeNode>,
+    /// Path end the depths and path flags were last built for, `None` once
+    /// the tree has changed since.
+    built_for: Option<Puzzle>,
+    /// Animation frame boards added now are drawn in.
+    frame: usize,
+    layout: TreeLayout,
+    /// First free column of every level in a stable layout.
+    free_columns: Vec<i32>,
+}
+
+impl IncrementalDrawTree {
+    pub fn new(initial: Puzzle, layout: TreeLayout) -> Self {
+        let root = DrawTreeNode::new_rc_ref(initial, initial, 0, true);
+        if layout == TreeLayout::Stable {
+            root.borrow_mut().column = Some(0);
+        }
+        IncrementalDrawTree {
+            nodes: HashMap::from([(initial, root.clone())]),
+            root,
+            built_for: None,
+            frame: 0,
+            layout,
+            free_columns: Vec::new(),
+        }
+    }
+
+    /// Gives `node` a column under `parent` in a stable layout: the
+    /// parent's own, or the first free one of its level to the right.
+    fn place(&mut self, node: &RcRefDrawTreeNode, parent: &RcRefDrawTreeNode) {
+        let depth = parent.borrow().depth + 1;
+        node.borrow_mut().depth = depth;
+        if self.layout != TreeLayout::Stable {
+            return;
+        }
+
+        if self.free_columns.len() <= depth as usize {
+            self.free_columns.resize(depth as usize + 1, i32::MIN);
+        }
+        let free = &mut self.free_columns[depth as usize];
+        let column = parent.borrow().column.unwrap_or(0).max(*free);
+        *free = column + 1;
+        node.borrow_mut().column = Some(column);
+    }
+
+    /// Ends the current animation frame and returns its number, boards
+    /// added from now on belong to the next one.
+    pub fn end_frame(&mut self) -> usize {
+        self.frame += 1;
+        self.frame - 1
+    }
+
+    /// Records that `puzzle` was closed with `parent` and `g`.
+    pub fn close(&mut self, puzzle: Puzzle, (parent, g): (Puzzle, i32)) {
+        self.update(puzzle, parent, g, false);
+    }
+
+    /// Records that `puzzle` was pushed to the open set with `parent` and
+    /// `g`. Returns whether that changed the tree: a closed board or a push
+    /// that lost to a cheaper path already in the open set does not.
+    pub fn open(&mut self, puzzle: Puzzle, (parent, g): (Puzzle, i32)) -> bool {
+        if let Some(node) = self.nodes.get(&puzzle) {
+            let node = node.borrow();
+            if !node.is_open_set || (node.parent == parent && node.g == g) {
+                return false;
+            }
+        }
+        self.update(puzzle, parent, g, true)
+    }
+
+    fn update(&mut self, puzzle: Puzzle, parent: Puzzle, g: i32, is_open_set: bool) -> bool {
+        let Some(parent_node) = self.nodes.get(&parent).cloned() else {
+            // Only boards hanging from the tree are drawn.
+            return false;
+        };
+
+        match self.nodes.get(&puzzle).cloned() {
+            Some(node) => {
+                // A cheaper path moves the board under its new parent. Only
+                // open boards move, and those have no children yet.
+                let old_parent = node.borrow().parent;
+                if old_parent != parent && puzzle != parent {
+                    if let Some(old_parent_node) = self.nodes.get(&old_parent) {
+                        old_parent_node
+                            .borrow_mut()
+                            .children
+                            .retain(|child| !Rc::ptr_eq(child, &node));
+                    }
+                    parent_node.borrow_mut().children.push(node.clone());
+                    // A board moved to another level needs a free column
+                    // there, otherwise it keeps its place.
+                    if node.borrow().depth != parent_node.borrow().depth + 1 {
+                        self.place(&node, &parent_node);
+                    }
+                }
+
+                let mut inner = node.borrow_mut();
+                inner.parent = parent;
+                inner.g = g;
+                inner.is_open_set = is_open_set;
+            }
+            None => {
+                let node = DrawTreeNode::new_rc_ref(puzzle, parent, g, is_open_set);
+                node.borrow_mut().added_at = self.frame;
+                self.place(&node, &parent_node);
+                parent_node.borrow_mut().children.push(node.clone());
+                self.nodes.insert(puzzle, node);
+            }
+        }
+
+        self.built_for = None;
+        self.root.borrow().coord_built_for.set(None);
+        true
+    }
+
+    /// The node drawing `puzzle`, if it hangs from the tree.
+    pub fn node(&self, puzzle: &Puzzle) -> Option<&RcRefDrawTreeNode> {
+        self.nodes.get(puzzle)
+    }
+
+    /// The tree with the path to `path_end` highlighted.
+    pub fn root(&mut self, path_end: &Puzzle) -> &RcRefDrawTreeNode {
+        if self.built_for != Some(*path_end) {
+            self.root.build_depth(0, path_end);
+            self.built_for = Some(*path_end);
+        }
+        &self.root
     }
 }
 
@@ -318,19 +833,35 @@ impl<'a, T: AsMapSearchTree>
     }
 }
 
+/// Size of the boards drawn in a tree. `scale` may be fractional for smooth
+/// zooming, it is rounded to whole pixels once in `puzzle_cell`, which every
+/// other length derives from.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PuzzleSizer {
-    pub scale: i32,
+    pub scale: f32,
 }
 
 impl PuzzleSizer {
-    fn puzzle_cell(&self) -> i32 {
-        2 * self.scale - 1
+    pub fn puzzle_cell(&self) -> i32 {
+        ((2.0 * self.scale - 1.0).round() as i32).max(1)
+    }
+
+    /// Whether a tree laid out for `other` is laid out for this sizer too.
+    /// The layout only depends on the rounded cell size, so scales a
+    /// fraction of a pixel apart share it.
+    pub fn same_layout(&self, other: &PuzzleSizer) -> bool {
+        self.puzzle_cell() == other.puzzle_cell()
     }
 
-    fn puzzle_center_offset(&self) -> i32 {
+    pub fn puzzle_center_offset(&self) -> i32 {
         self.puzzle_cell() * 3 / 2
     }
+
+    /// Center of the boards at `depth`, below the top of the root's level.
+    pub fn depth_y(&self, depth: u32) -> i32 {
+        depth as i32 * ((4 * self.puzzle_cell() + 1) + self.puzzle_cell() * 3)
+            + self.puzzle_center_offset()
+    }
 }
 
 pub struct ElementPainter<'a, 'b> {
@@ -338,6 +869,27 @@ pub struct ElementPainter<'a, 'b> {
     pub bound: IntRectBound,
     pub offset: (i32, i32),
     pub sizer: PuzzleSizer,
+    pub theme: Theme,
+    /// Deepest level drawn, `None` draws as deep as the bound allows.
+    pub max_render_depth: Option<u32>,
+    /// Edge width in pixels per unit of `sizer.scale`.
+    pub edge_thickness: f32,
+    /// Largest g in the tree. When set, edges off the solution path are
+    /// colored by the g of the board they lead to, on `g_ramp_color`.
+    pub g_ramp: Option<i32>,
+    /// When set, a square in the color of its f on `range_ramp_color` is
+    /// drawn behind every board.
+    pub f_ramp: Option<&'b FValues>,
+    /// When set, a square in the color of the part of the heuristic that
+    /// estimated it is drawn behind every board, as for `f_ramp`.
+    pub sources: Option<&'b HeuristicSources>,
+    /// Animation frame to highlight. When set, boards added in it are framed
+    /// and older ones fade towards the background, showing the wavefront.
+    pub fresh_frame: Option<usize>,
+    /// Mark where parts of the tree are off to the side of the bound.
+    pub ghosts: bool,
+    /// Most edges drawn per frame, `None` draws all of them.
+    pub max_edges: Option<usize>,
 }
 
 impl Deref for ElementPainter<'_, '_> {
@@ -359,78 +911,215 @@ impl ElementPainter<'_, '_> {
         }
     }
 
+    fn is_truncated(&self, depth: u32) -> bool {
+        self.max_render_depth.is_some_and(|max| depth >= max)
+    }
+
     fn get_draw_y(&self, depth: u32) -> Option<i32> {
-        match self.offset.1
-            + depth as i32 * ((4 * self.puzzle_cell() + 1) + self.puzzle_cell() * 3)
-            + self.puzzle_center_offset()
-        {
+        if self.max_render_depth.is_some_and(|max| depth > max) {
+            return None;
+        }
+
+        match self.offset.1 + self.depth_y(depth) {
             x if x > self.bound.bottom => None,
             x => Some(x),
         }
     }
 
-    fn draw_small_puzzle(
-        &mut self,
-        puzzle: &Puzzle,
-        x: i32,
-        y: i32,
-        on_path: bool,
-        open_set: bool,
-    ) {
+    /// Depth labels down the left edge of the bound, one per level, moving
+    /// with vertical panning only.
+    pub fn draw_depth_axis(&mut self) {
+        let (left, top) = (self.bound.left, self.bound.top);
+        let height = self.bound.bottom - top;
+        self.draw_handle
+            .draw_rectangle(left, top, DEPTH_AXIS_WIDTH, height, self.theme.panel);
+        self.draw_handle
+            .draw_rectangle(left + DEPTH_AXIS_WIDTH, top, 1, height, self.theme.edge);
+
+        let mut depth = 0;
+        while let Some(y) = self.get_draw_y(depth) {
+            if y >= top {
+                self.draw_handle.draw_text(
+                    &depth.to_string(),
+                    left + 4,
+                    y - 5,
+                    10,
+                    self.theme.text,
+                );
+            }
+            depth += 1;
+        }
+    }
+
+    fn draw_small_puzzle(&mut self, node: &DrawTreeNode, x: i32, y: i32) {
+        let fresh = self.fresh_frame.map(|frame| node.added_at >= frame);
+        if let Some(color) = self
+            .f_ramp
+            .and_then(|f_values| f_values.color(&node.puzzle))
+            .or_else(|| self.sources.and_then(|sources| sources.color(&node.puzzle)))
+        {
+            let half = self.puzzle_center_offset() + 4;
+            self.draw_handle
+                .draw_rectangle(x - half, y - half, 2 * half + 1, 2 * half + 1, color);
+        }
         draw_small_puzzle(
             self.draw_handle,
-            puzzle,
+            &self.theme,
+            &node.puzzle,
             SmallPuzzleCenter {
                 x,
                 y,
                 cell_size: self.puzzle_cell(),
             },
-            match on_path {
-                true => Some(Color::RED),
-                false => match open_set {
-                    true => Some(Color::AQUA),
-                    false => None,
-                },
+            match (node.on_path.get(), fresh, node.is_open_set) {
+                (true, _, _) => Some(self.theme.path),
+                (false, Some(true), _) => Some(Color::GOLD),
+                (false, _, true) => Some(Color::AQUA),
+                (false, _, false) => None,
             },
         );
+
+        if fresh == Some(false) {
+            let half = self.puzzle_center_offset() + 2;
+            self.draw_handle.draw_rectangle(
+                x - half,
+                y - half,
+                2 * half + 1,
+                2 * half + 1,
+                self.theme.background.fade(0.7),
+            );
+        }
     }
 
-    fn draw_line_up(&mut self, x: i32, y: i32, on_path: bool) {
-        self.draw_handle.draw_rectangle(
-            x,
-            y - self.puzzle_cell() * 2 - self.puzzle_center_offset(),
-            1,
-            self.puzzle_cell(),
-            if on_path { Color::RED } else { Color::BLACK },
+    /// Screen position of `node`'s center, `None` when its level is not
+    /// drawn. Nodes off to the side are clamped to the bound, as drawn.
+    pub fn node_position(&self, node: &RcRefDrawTreeNode) -> Option<(i32, i32)> {
+        let node = node.borrow();
+        Some((self.get_draw_x(node.center_x), self.get_draw_y(node.depth)?))
+    }
+
+    /// Frame around the selected node.
+    pub fn draw_selection(&mut self, node: &RcRefDrawTreeNode) {
+        if let Some((x, y)) = self.node_position(node) {
+            let half = self.puzzle_center_offset() + 4;
+            let rectangle = Rectangle::new(
+                (x - half) as f32,
+                (y - half) as f32,
+                (2 * half + 1) as f32,
+                (2 * half + 1) as f32,
+            );
+            self.draw_handle
+                .draw_rectangle_lines_ex(rectangle, 2.0, self.theme.accent);
+        }
+    }
+
+    /// A faint board at the edge of the bound on level `depth`, with the
+    /// number of boards `count` off screen past it.
+    fn draw_ghost(&mut self, depth: u32, right: bool, count: usize) {
+        let Some(y) = self.get_draw_y(depth).filter(|&y| y >= self.bound.top) else {
+            return;
+        };
+        let half = self.puzzle_center_offset();
+        let text = format!("+{}", count);
+        let text_width = measure_text(&text, 10);
+        let (x, text_x) = match right {
+            true => {
+                let x = self.bound.right - half - 2;
+                (x, x - half - 4 - text_width)
+            }
+            false => {
+                let x = self.bound.left + DEPTH_AXIS_WIDTH + half + 3;
+                (x, x + half + 4)
+            }
+        };
+
+        let color = self.theme.edge.fade(0.5);
+        self.draw_handle.draw_rectangle_lines(
+            x - half - 1,
+            y - half - 1,
+            2 * half + 3,
+            2 * half + 3,
+            color,
         );
+        self.draw_handle.draw_text(&text, text_x, y - 5, 10, color);
     }
 
-    fn draw_line_down(&mut self, x: i32, y: i32, on_path: bool) {
-        self.draw_handle.draw_rectangle(
-            x,
-            y + 1 + self.puzzle_cell() + self.puzzle_center_offset(),
-            1,
-            self.puzzle_cell(),
-            if on_path { Color::RED } else { Color::BLACK },
+    fn draw_truncation_marker(&mut self, x: i32, y: i32) {
+        self.draw_handle.draw_text(
+            "...",
+            x - measure_text("...", 10) / 2,
+            y + self.puzzle_center_offset() + self.puzzle_cell(),
+            10,
+            self.theme.edge,
         );
     }
 
-    fn draw_line_across(&mut self, left_x: i32, right_x: i32, y: i32) {
-        let line_y = y + 1 + 2 * self.puzzle_cell() + self.puzzle_center_offset();
+    /// Edge width in pixels, never thinner than one pixel.
+    fn edge_width(&self) -> f32 {
+        (self.edge_thickness * self.scale).max(1.0)
+    }
 
+    /// Edge from `(x, from_y)` to `(x, to_y)`, centered on pixel column `x`.
+    fn draw_vertical_edge(&mut self, x: i32, from_y: f32, to_y: f32, color: Color) {
+        let x = x as f32 + 0.5;
+        let width = self.edge_width();
         self.draw_handle
-            .draw_rectangle(left_x, line_y, right_x - left_x + 1, 1, Color::BLACK);
+            .draw_line_ex(Vector2::new(x, from_y), Vector2::new(x, to_y), width, color);
+    }
+
+    /// Edge covering pixel columns `left_x..=right_x`, centered on row `y`.
+    fn draw_horizontal_edge(&mut self, left_x: i32, right_x: i32, y: i32, color: Color) {
+        let y = y as f32 + 0.5;
+        let width = self.edge_width();
+        self.draw_handle.draw_line_ex(
+            Vector2::new(left_x as f32, y),
+            Vector2::new(right_x as f32 + 1.0, y),
+            width,
+            color,
+        );
+    }
+
+    /// Pixel row of the horizontal edge below a node drawn at `y`. Vertical
+    /// edges run to the middle of this row so thick edges meet cleanly.
+    fn across_line_y(&self, y: i32) -> i32 {
+        y + 1 + 2 * self.puzzle_cell() + self.puzzle_center_offset()
+    }
+
+    fn edge_color(&self, on_path: bool, g: i32) -> Color {
+        match (on_path, self.g_ramp) {
+            (true, _) => self.theme.path,
+            (false, Some(max_g)) => g_ramp_color(g, max_g),
+            (false, None) => self.theme.edge,
+        }
+    }
+
+    fn draw_line_up(&mut self, x: i32, y: i32, on_path: bool, g: i32) {
+        let bottom = y - self.puzzle_cell() - self.puzzle_center_offset();
+        let top = bottom - self.puzzle_cell();
+        let color = self.edge_color(on_path, g);
+        self.draw_vertical_edge(x, top as f32 - 0.5, bottom as f32, color);
+    }
+
+    fn draw_line_down(&mut self, x: i32, y: i32, on_path: bool, g: i32) {
+        let top = y + 1 + self.puzzle_cell() + self.puzzle_center_offset();
+        let bottom = self.across_line_y(y);
+        let color = self.edge_color(on_path, g);
+        self.draw_vertical_edge(x, top as f32, bottom as f32 + 0.5, color);
+    }
+
+    fn draw_line_across(&mut self, left_x: i32, right_x: i32, y: i32, g: i32) {
+        let line_y = self.across_line_y(y);
+        let color = self.edge_color(false, g);
+        self.draw_horizontal_edge(left_x, right_x, line_y, color);
     }
 
     fn draw_line_across_on_path(&mut self, x: i32, y: i32, other_end: i32) {
-        let line_y = y + 1 + 2 * self.puzzle_cell() + self.puzzle_center_offset();
+        let line_y = self.across_line_y(y);
         let (start_x, end_x) = match x < other_end {
             true => (x, other_end),
             false => (other_end, x),
         };
-
-        self.draw_handle
-            .draw_rectangle(start_x, line_y, end_x - start_x + 1, 1, Color::RED);
+        self.draw_horizontal_edge(start_x, end_x, line_y, self.theme.path);
     }
 }
 
@@ -441,3 +1130,184 @@ impl<T: AsMapSearchTree> From<MapSearchTree<'_, T>>
         RcRefDrawTreeNode::new_from_map_search_tree(&tree, tree.goal())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nine::logic::{Direction, GoalPreset, Heuristic, ManhattanOrMisplaced};
+
+    use super::*;
+
+    const SIZER: PuzzleSizer = PuzzleSizer { scale: 3.0 };
+
+    fn center_x(tree: &mut IncrementalDrawTree, puzzle: &Puzzle) -> i32 {
+        let root = tree.root(puzzle).clone();
+        root.build_coord(&SIZER);
+        let x = root.find(puzzle).unwrap().borrow().center_x;
+        x
+    }
+
+    /// Search tree listing its boards in the order they were given.
+    struct ListedTree {
+        initial: Puzzle,
+        entries: Vec<(Puzzle, (Puzzle, i32))>,
+    }
+
+    impl<'a> IterableSearchTree<'a, std::vec::IntoIter<(&'a Puzzle, &'a (Puzzle, i32), bool)>>
+        for ListedTree
+    {
+        fn initial(&self) -> &Puzzle {
+            &self.initial
+        }
+
+        fn goal(&self) -> &Puzzle {
+            &self.initial
+        }
+
+        fn iter(&'a self) -> std::vec::IntoIter<(&'a Puzzle, &'a (Puzzle, i32), bool)> {
+            let entries = self.entries.iter();
+            entries
+                .map(|(puzzle, value)| (puzzle, value, false))
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+    }
+
+    /// Boards of the tree under `node`, every parent before its children.
+    fn preorder(node: &RcRefDrawTreeNode) -> Vec<Puzzle> {
+        let node = node.borrow();
+        let mut boards = vec![node.puzzle];
+        boards.extend(node.children().iter().flat_map(preorder));
+        boards
+    }
+
+    #[test]
+    fn boards_of_one_f_share_a_color() {
+        let initial = GoalPreset::Spiral.puzzle();
+        let up = initial.move_zero(Direction::Up).unwrap();
+        let down = initial.move_zero(Direction::Down).unwrap();
+        let map = HashMap::from([
+            (initial, (initial, 0)),
+            (up, (initial, 1)),
+            (down, (initial, 1)),
+        ]);
+        let f_values = FValues::new(&map, |puzzle| match *puzzle == down {
+            true => 5,
+            false => 4 - map[puzzle].1,
+        });
+
+        assert_eq!(f_values.range(), (4, 6));
+        assert_eq!((f_values.f[&initial], f_values.f[&up]), (4, 4));
+        assert_eq!(f_values.f[&down], 6);
+        assert_eq!(f_values.color(&initial), f_values.color(&up));
+        let elsewhere = up.move_zero(Direction::Left).unwrap();
+        assert_eq!(f_values.color(&elsewhere), None);
+    }
+
+    #[test]
+    fn boards_are_colored_by_the_winning_part() {
+        let goal = GoalPreset::Spiral.puzzle();
+        let one_move = goal.move_zero(Direction::Up).unwrap();
+        let two_moves = one_move.move_zero(Direction::Left).unwrap();
+        let map = HashMap::from([
+            (two_moves, (two_moves, 0)),
+            (one_move, (two_moves, 1)),
+            (goal, (one_move, 2)),
+        ]);
+        let mut max = ManhattanOrMisplaced::new();
+        let sources = HeuristicSources::new(&map, max.source_names(), |puzzle| {
+            max.estimate_with_source(puzzle, &goal).1
+        });
+
+        assert_eq!(sources.names(), ["Manhattan", "A* (1)"]);
+        assert_eq!(sources.source[&goal], 0);
+        assert_eq!(sources.source[&one_move], 1);
+        assert_eq!(sources.color(&goal), Some(SOURCE_COLORS[0]));
+        assert_eq!(sources.color(&one_move), Some(SOURCE_COLORS[1]));
+        let elsewhere = goal.move_zero(Direction::Down).unwrap();
+        assert_eq!(sources.color(&elsewhere), None);
+    }
+
+    #[test]
+    fn children_order_does_not_depend_on_listing_order() {
+        let initial = GoalPreset::Spiral.puzzle();
+        let mut entries = vec![(initial, (initial, 0))];
+        for direction in Direction::all() {
+            let child = initial.move_zero(direction).unwrap();
+            entries.push((child, (initial, 1)));
+            for grandchild in Direction::all().map(|direction| child.move_zero(direction)) {
+                match grandchild {
+                    Some(grandchild) if grandchild != initial => {
+                        entries.push((grandchild, (child, 2)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let listed = ListedTree {
+            initial,
+            entries: entries.clone(),
+        };
+        entries.reverse();
+        let reversed = ListedTree { initial, entries };
+
+        let (listed_root, _) = RcRefDrawTreeNode::new_from_map_search_tree(&listed, &initial);
+        let (reversed_root, _) = RcRefDrawTreeNode::new_from_map_search_tree(&reversed, &initial);
+        assert_eq!(preorder(&listed_root).len(), listed.entries.len());
+        assert_eq!(preorder(&listed_root), preorder(&reversed_root));
+    }
+
+    #[test]
+    fn parents_are_centered_over_even_and_odd_widths() {
+        // Cells of 4 pixels: a board spans 13 pixels, 4 apart from the next.
+        let sizer = PuzzleSizer { scale: 2.5 };
+        let initial = GoalPreset::Spiral.puzzle();
+        let up = initial.move_zero(Direction::Up).unwrap();
+        let down = initial.move_zero(Direction::Down).unwrap();
+        let [up_left, up_right] =
+            [Direction::Left, Direction::Right].map(|direction| up.move_zero(direction).unwrap());
+
+        let mut tree = IncrementalDrawTree::new(initial, TreeLayout::Tidy);
+        tree.open(up, (initial, 1));
+        tree.open(down, (initial, 1));
+        tree.open(up_left, (up, 2));
+        tree.open(up_right, (up, 2));
+        let root = tree.root(&initial).clone();
+        root.build_coord(&sizer);
+        let span = |puzzle: &Puzzle| {
+            let node = root.find(puzzle).unwrap();
+            let node = node.borrow();
+            (node.min_x, node.center_x, node.max_x)
+        };
+
+        // `up` spans two boards and a gap, 30 pixels, one more right of its
+        // center than left of it. With `down` the root spans 47, evenly.
+        assert_eq!(span(&initial), (-23, 0, 23));
+        assert_eq!(span(&up), (-23, -9, 6));
+        assert_eq!(span(&up_left), (-23, -17, -11));
+        assert_eq!(span(&up_right), (-6, 0, 6));
+        assert_eq!(span(&down), (11, 17, 23));
+    }
+
+    #[test]
+    fn stable_layout_keeps_placed_boards_in_place() {
+        let initial = GoalPreset::Spiral.puzzle();
+        let [up, down, left, right] =
+            Direction::all().map(|direction| initial.move_zero(direction).unwrap());
+        let below_up = up.move_zero(Direction::Left).unwrap();
+
+        for (layout, moves) in [(TreeLayout::Tidy, true), (TreeLayout::Stable, false)] {
+            let mut tree = IncrementalDrawTree::new(initial, layout);
+            tree.open(up, (initial, 1));
+            tree.open(down, (initial, 1));
+            let before = [center_x(&mut tree, &up), center_x(&mut tree, &down)];
+
+            tree.open(left, (initial, 1));
+            tree.open(right, (initial, 1));
+            tree.open(below_up, (up, 2));
+            let after = [center_x(&mut tree, &up), center_x(&mut tree, &down)];
+
+            assert_eq!(before != after, moves, "{:?}", layout);
+        }
+    }
+}
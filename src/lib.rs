@@ -0,0 +1,5 @@
+pub mod bench;
+pub mod board_library;
+pub mod logic;
+pub mod search_dump;
+pub mod session;
@@ -1,45 +1,63 @@
 use super::{Heuristic, Puzzle};
 
-pub struct AStarHeuristic1 {}
+/// Misplaced tiles, the blank counted as one more when `COUNT_BLANK` is set.
+/// Counting the blank can overestimate: a board one move from the goal has
+/// two misplaced cells. Without it every move puts at most one tile in
+/// place, so the count is admissible and consistent.
+pub struct MisplacedTiles<const COUNT_BLANK: bool> {}
 
-impl Heuristic for AStarHeuristic1 {
+/// Misplaced cells, blank included.
+pub type AStarHeuristic1 = MisplacedTiles<true>;
+
+/// Misplaced tiles, blank excluded.
+pub type AStarHeuristic2 = MisplacedTiles<false>;
+
+impl<const COUNT_BLANK: bool> Heuristic for MisplacedTiles<COUNT_BLANK> {
     fn new() -> Self {
-        AStarHeuristic1 {}
+        MisplacedTiles {}
     }
 
-    fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
-        let mut count = 0;
-
-        for i in 0..3 {
-            for j in 0..3 {
-                if current.get_value(i, j) != goal.get_value(i, j) {
-                    count += 1;
-                }
-            }
+    fn name(&self) -> &'static str {
+        match COUNT_BLANK {
+            true => "A* (1)",
+            false => "A* (2)",
         }
+    }
 
-        count
+    fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
+        current.misplaced_tiles(goal, COUNT_BLANK)
+    }
+
+    fn is_admissible(&self) -> bool {
+        !COUNT_BLANK
+    }
+
+    fn is_consistent(&self) -> bool {
+        !COUNT_BLANK
     }
 }
 
-pub struct AStarHeuristic2 {}
+/// Manhattan distance of every tile to its place, blank excluded.
+pub struct ManhattanHeuristic {}
 
-impl Heuristic for AStarHeuristic2 {
+impl Heuristic for ManhattanHeuristic {
     fn new() -> Self {
-        AStarHeuristic2 {}
+        ManhattanHeuristic {}
+    }
+
+    fn name(&self) -> &'static str {
+        "Manhattan"
     }
 
     fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
-        let mut count = 0;
-
-        for i in 0..3 {
-            for j in 0..3 {
-                if current.get_value(i, j) != goal.get_value(i, j) && current.get_value(i, j) != 0 {
-                    count += 1;
-                }
-            }
-        }
+        current.manhattan_to(goal)
+    }
+
+    fn is_admissible(&self) -> bool {
+        true
+    }
 
-        count
+    fn is_consistent(&self) -> bool {
+        true
     }
 }
@@ -7,7 +7,19 @@ impl Heuristic for BfsHeuristic {
         BfsHeuristic {}
     }
 
+    fn name(&self) -> &'static str {
+        "BFS"
+    }
+
     fn estimate_h(&mut self, _current: &Puzzle, _goal: &Puzzle) -> i32 {
         0
     }
+
+    fn is_admissible(&self) -> bool {
+        true
+    }
+
+    fn is_consistent(&self) -> bool {
+        true
+    }
 }
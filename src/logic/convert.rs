@@ -0,0 +1,157 @@
+use std::{fmt, str::FromStr};
+
+use super::Puzzle;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The board does not have exactly nine cells.
+    WrongLength(usize),
+    /// A cell holds something other than 0 (the blank) to 8.
+    OutOfRange(u8),
+    Duplicate(u8),
+    InvalidToken(String),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::WrongLength(len) => write!(f, "expected 9 cells, found {}", len),
+            PuzzleError::OutOfRange(value) => write!(f, "tile {} is out of range 0-8", value),
+            PuzzleError::Duplicate(value) => write!(f, "tile {} appears more than once", value),
+            PuzzleError::InvalidToken(token) => write!(f, "invalid tile \"{}\"", token),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl TryFrom<[u8; 9]> for Puzzle {
+    type Error = PuzzleError;
+
+    fn try_from(cells: [u8; 9]) -> Result<Self, Self::Error> {
+        let mut seen = [false; 9];
+
+        for &value in cells.iter() {
+            match seen.get_mut(value as usize) {
+                None => return Err(PuzzleError::OutOfRange(value)),
+                Some(true) => return Err(PuzzleError::Duplicate(value)),
+                Some(seen) => *seen = true,
+            }
+        }
+
+        let mut board = [[0; 3]; 3];
+        for (index, &value) in cells.iter().enumerate() {
+            let (i, j) = Puzzle::index_to_coord(index);
+            board[i][j] = value;
+        }
+        Ok(Puzzle::new(board))
+    }
+}
+
+impl Puzzle {
+    /// The board on one line, rows split by slashes: `"1 2 3 / 8 0 4 / 7 6 5"`.
+    /// Fits a log line or a CSV cell where `Display` takes three lines, and
+    /// parses back.
+    pub fn to_compact(&self) -> String {
+        self.board
+            .iter()
+            .map(|row| row.map(|value| value.to_string()).join(" "))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+/// Parses nine cells in row-major order separated by whitespace, commas or
+/// slashes, e.g. `"1 2 3 8 0 4 7 6 5"` or `"1 2 3 / 8 0 4 / 7 6 5"`.
+impl FromStr for Puzzle {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = s
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '/')
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                token
+                    .parse::<u8>()
+                    .map_err(|_| PuzzleError::InvalidToken(token.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let len = cells.len();
+        let cells: [u8; 9] = cells
+            .try_into()
+            .map_err(|_| PuzzleError::WrongLength(len))?;
+
+        Puzzle::try_from(cells)
+    }
+}
+
+impl TryFrom<&str> for Puzzle {
+    type Error = PuzzleError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_form_round_trips() {
+        let puzzle = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        assert_eq!(puzzle.to_compact(), "1 2 3 / 8 0 4 / 7 6 5");
+        assert_eq!(puzzle.to_compact().parse(), Ok(puzzle));
+        assert_eq!("1,2,3/8,0,4/7,6,5".parse(), Ok(puzzle));
+        assert_eq!(
+            "1 2 3 / 8 0 4".parse::<Puzzle>(),
+            Err(PuzzleError::WrongLength(6))
+        );
+    }
+
+    #[test]
+    fn cells_convert_row_major() {
+        let cells = [1, 2, 3, 8, 0, 4, 7, 6, 5];
+        let puzzle = Puzzle::try_from(cells).unwrap();
+        assert_eq!(puzzle, Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]));
+        assert_eq!(puzzle.to_compact().parse(), Ok(puzzle));
+    }
+
+    #[test]
+    fn wrong_length_is_rejected() {
+        assert_eq!(
+            "1 2 3 8 0 4 7 6 5 9".parse::<Puzzle>(),
+            Err(PuzzleError::WrongLength(10))
+        );
+        assert_eq!("".parse::<Puzzle>(), Err(PuzzleError::WrongLength(0)));
+    }
+
+    #[test]
+    fn out_of_range_tile_is_rejected() {
+        assert_eq!(
+            Puzzle::try_from([1, 2, 3, 8, 9, 4, 7, 6, 5]),
+            Err(PuzzleError::OutOfRange(9))
+        );
+    }
+
+    #[test]
+    fn duplicate_tile_is_rejected() {
+        assert_eq!(
+            Puzzle::try_from([1, 2, 3, 8, 0, 4, 7, 6, 1]),
+            Err(PuzzleError::Duplicate(1))
+        );
+    }
+
+    #[test]
+    fn invalid_token_is_rejected() {
+        assert_eq!(
+            "1 2 3 8 x 4 7 6 5".parse::<Puzzle>(),
+            Err(PuzzleError::InvalidToken("x".to_string()))
+        );
+        assert_eq!(
+            "1 2 3 8 -1 4 7 6 5".parse::<Puzzle>(),
+            Err(PuzzleError::InvalidToken("-1".to_string()))
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use super::Puzzle;
+
+/// Looks for parent links in a search map that loop back on themselves
+/// without reaching a root, a root being a board that is its own parent.
+/// Following the parents from any board of such a loop never terminates.
+///
+/// Returns the boards of the first loop found, each one the parent of the
+/// one before it. A parent missing from the map ends its chain like a root.
+pub fn find_parent_cycle(map: &HashMap<Puzzle, (Puzzle, i32)>) -> Option<Vec<Puzzle>> {
+    // Boards whose chain is known to end, and the chain being followed.
+    let mut done = HashMap::with_capacity(map.len());
+    let mut chain = Vec::new();
+
+    for &start in map.keys() {
+        let mut current = start;
+        while let Some(&(parent, _)) = map.get(&current) {
+            match done.get(&current) {
+                Some(&true) => break,
+                Some(&false) => {
+                    let from = chain.iter().position(|board| *board == current)?;
+                    return Some(chain.split_off(from));
+                }
+                None => {}
+            }
+
+            done.insert(current, false);
+            chain.push(current);
+            if parent == current {
+                break;
+            }
+            current = parent;
+        }
+
+        for board in chain.drain(..) {
+            done.insert(board, true);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(first: u8) -> Puzzle {
+        let mut values = [[1, 2, 3], [8, 0, 4], [7, 6, 5]];
+        values[0][0] = first;
+        Puzzle::new(values)
+    }
+
+    #[test]
+    fn tree_has_no_cycle() {
+        let map = HashMap::from([
+            (board(1), (board(1), 0)),
+            (board(2), (board(1), 1)),
+            (board(3), (board(2), 2)),
+            (board(4), (board(1), 1)),
+            // Parent outside of the map.
+            (board(5), (board(9), 3)),
+        ]);
+
+        assert_eq!(find_parent_cycle(&map), None);
+    }
+
+    #[test]
+    fn finds_cycle_behind_a_tail() {
+        let map = HashMap::from([
+            (board(1), (board(1), 0)),
+            (board(2), (board(3), 1)),
+            (board(3), (board(4), 2)),
+            (board(4), (board(2), 3)),
+            (board(5), (board(2), 4)),
+        ]);
+
+        let mut cycle = find_parent_cycle(&map).unwrap();
+        assert_eq!(cycle.len(), 3);
+        for (i, current) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            assert_eq!(map[current].0, next);
+        }
+
+        cycle.sort_by_key(|board| board.get_value(0, 0));
+        assert_eq!(cycle, vec![board(2), board(3), board(4)]);
+    }
+}
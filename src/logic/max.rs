@@ -0,0 +1,97 @@
+use super::{AStarHeuristic1, Direction, Heuristic, ManhattanHeuristic, Puzzle};
+
+/// The larger of the estimates of `A` and `B`. It is admissible or
+/// consistent only when both parts are, and it always estimates at least as
+/// much as either part alone. Step costs are the ones of `A`.
+pub struct MaxHeuristic<A: Heuristic, B: Heuristic> {
+    first: A,
+    second: B,
+}
+
+/// Manhattan distance or misplaced cells with the blank, whichever is
+/// larger. Near the goal the misplaced cells win, further away Manhattan
+/// does. Counting the blank makes it inadmissible, as `AStarHeuristic1` is.
+pub type ManhattanOrMisplaced = MaxHeuristic<ManhattanHeuristic, AStarHeuristic1>;
+
+impl<A: Heuristic, B: Heuristic> MaxHeuristic<A, B> {
+    /// The estimate along with the part that gave it, 0 for `A` and 1 for
+    /// `B`. Ties go to `A`.
+    pub fn estimate_with_source(&mut self, current: &Puzzle, goal: &Puzzle) -> (i32, usize) {
+        let first = self.first.estimate_h(current, goal);
+        let second = self.second.estimate_h(current, goal);
+        match second > first {
+            true => (second, 1),
+            false => (first, 0),
+        }
+    }
+
+    /// Names of the parts, indexed by the source of `estimate_with_source`.
+    pub fn source_names(&self) -> [&'static str; 2] {
+        [self.first.name(), self.second.name()]
+    }
+}
+
+impl<A: Heuristic, B: Heuristic> Heuristic for MaxHeuristic<A, B> {
+    fn new() -> Self {
+        MaxHeuristic {
+            first: A::new(),
+            second: B::new(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Max"
+    }
+
+    fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
+        self.estimate_with_source(current, goal).0
+    }
+
+    fn cost(&self, from: &Puzzle, to: &Puzzle, direction: Direction) -> i32 {
+        self.first.cost(from, to, direction)
+    }
+
+    fn is_admissible(&self) -> bool {
+        self.first.is_admissible() && self.second.is_admissible()
+    }
+
+    fn is_consistent(&self) -> bool {
+        self.first.is_consistent() && self.second.is_consistent()
+    }
+
+    fn on_expand(&mut self, node: &Puzzle, g: i32) {
+        self.first.on_expand(node, g);
+        self.second.on_expand(node, g);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    #[test]
+    fn source_is_the_larger_part() {
+        let mut max = ManhattanOrMisplaced::new();
+
+        // Two swapped neighbours: Manhattan 2, misplaced cells 2, a tie.
+        let swapped = Puzzle::new([[2, 1, 3], [8, 0, 4], [7, 6, 5]]);
+        assert_eq!(max.estimate_with_source(&swapped, &goal()), (2, 0));
+
+        // One move from the goal: Manhattan 1, misplaced cells 2 with the
+        // blank.
+        let one_move = Puzzle::new([[1, 0, 3], [8, 2, 4], [7, 6, 5]]);
+        assert_eq!(max.estimate_with_source(&one_move, &goal()), (2, 1));
+
+        // Far corners swapped: Manhattan 8, misplaced cells 2.
+        let corners = Puzzle::new([[5, 2, 3], [8, 0, 4], [7, 6, 1]]);
+        assert_eq!(max.estimate_with_source(&corners, &goal()), (8, 0));
+        assert_eq!(max.estimate_h(&corners, &goal()), 8);
+
+        assert_eq!(max.source_names(), ["Manhattan", "A* (1)"]);
+        assert!(!max.is_admissible());
+    }
+}
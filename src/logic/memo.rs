@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use super::{Direction, Heuristic, Puzzle};
+
+/// Caches the estimates of `H` by board.
+///
+/// The cache is keyed on the board alone: a heuristic is created for a
+/// single search, so the goal passed to `estimate_h` is the same on every
+/// call. Reusing one `MemoHeuristic` across different goals would return
+/// estimates for the wrong goal. Estimates are never recomputed, so a
+/// heuristic that learns through `on_expand` only affects boards it has not
+/// estimated yet.
+pub struct MemoHeuristic<H: Heuristic> {
+    inner: H,
+    cache: HashMap<Puzzle, i32>,
+    hits: usize,
+}
+
+impl<H: Heuristic> MemoHeuristic<H> {
+    /// Estimates answered from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Estimates computed by `H` so far.
+    pub fn misses(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+impl<H: Heuristic> Heuristic for MemoHeuristic<H> {
+    fn new() -> Self {
+        MemoHeuristic {
+            inner: H::new(),
+            cache: HashMap::new(),
+            hits: 0,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
+        if let Some(&h) = self.cache.get(current) {
+            self.hits += 1;
+            return h;
+        }
+
+        let h = self.inner.estimate_h(current, goal);
+        self.cache.insert(*current, h);
+        h
+    }
+
+    fn cost(&self, from: &Puzzle, to: &Puzzle, direction: Direction) -> i32 {
+        self.inner.cost(from, to, direction)
+    }
+
+    fn is_admissible(&self) -> bool {
+        self.inner.is_admissible()
+    }
+
+    fn is_consistent(&self) -> bool {
+        self.inner.is_consistent()
+    }
+
+    fn on_expand(&mut self, node: &Puzzle, g: i32) {
+        self.inner.on_expand(node, g);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{solve_from_initials_with, ManhattanHeuristic, SearchProgress, SearchTree};
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    #[test]
+    fn second_estimate_of_a_board_is_a_hit() {
+        let board = goal().scrambled("U L D").unwrap();
+        let mut memo = MemoHeuristic::<ManhattanHeuristic>::new();
+
+        let first = memo.estimate_h(&board, &goal());
+        assert_eq!((memo.hits(), memo.misses()), (0, 1));
+        assert_eq!(memo.estimate_h(&board, &goal()), first);
+        assert_eq!((memo.hits(), memo.misses()), (1, 1));
+        assert_eq!(first, ManhattanHeuristic::new().estimate_h(&board, &goal()));
+    }
+
+    /// Closed set that keeps the last progress report of the search.
+    struct ClosedSet {
+        goal: Puzzle,
+        map: HashMap<Puzzle, (Puzzle, i32)>,
+        progress: SearchProgress,
+    }
+
+    impl SearchTree for ClosedSet {
+        fn goal_reached(&self) -> bool {
+            self.map.contains_key(&self.goal)
+        }
+        fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+            self.map.get(key).copied()
+        }
+        fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+            self.map.insert(key, value);
+        }
+        fn on_progress(&mut self, progress: &SearchProgress) {
+            self.progress = *progress;
+        }
+    }
+
+    #[test]
+    fn deep_solve_hit_rate() {
+        // 30 moves from the goal, as far as any board gets.
+        let initial = Puzzle::new([[5, 6, 7], [4, 0, 8], [3, 2, 1]]);
+        let mut closed_set = ClosedSet {
+            goal: goal(),
+            map: HashMap::new(),
+            progress: SearchProgress::default(),
+        };
+        let mut memo = MemoHeuristic::<ManhattanHeuristic>::new();
+        let (_, path) =
+            solve_from_initials_with(&[initial], goal(), &mut closed_set, &mut memo).unwrap();
+        assert_eq!(path.len(), 31);
+
+        // Every board pushed but the initial one is estimated once. Closed
+        // boards are skipped, so only a board reached again while still open
+        // is a hit: 21 of 1296 estimates, under 2%.
+        assert_eq!(
+            memo.hits() + memo.misses(),
+            closed_set.progress.generated - 1
+        );
+        assert_eq!((memo.hits(), memo.misses()), (21, 1275));
+    }
+}
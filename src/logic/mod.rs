@@ -1,14 +1,38 @@
 mod a_star;
 mod bfs;
+mod convert;
+mod cycles;
+mod max;
+mod memo;
+mod preset;
+mod record;
+mod scramble;
+mod share;
+mod solution_map;
+mod solver;
 
 use std::collections::{BinaryHeap, HashMap};
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-pub use a_star::{AStarHeuristic1, AStarHeuristic2};
+pub use a_star::{AStarHeuristic1, AStarHeuristic2, ManhattanHeuristic, MisplacedTiles};
 pub use bfs::BfsHeuristic;
+pub use convert::PuzzleError;
+pub use cycles::find_parent_cycle;
+pub use max::{ManhattanOrMisplaced, MaxHeuristic};
+pub use memo::MemoHeuristic;
+pub use preset::GoalPreset;
+pub use record::RecordExpansions;
+pub use scramble::{parse_moves, verify_path, ScrambleError};
+pub use share::{decode_share, encode_share, ShareError, SharedProblem};
+pub use solution_map::{SolutionMap, SolutionMapBuilder};
+pub use solver::{
+    solve, solve_bounded, solve_count, solve_in_order, solve_weighted, SearchStats, Solution,
+    SolveConfig, StepCount, Strategy,
+};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Puzzle {
     board: [[u8; 3]; 3],
 }
@@ -26,21 +50,52 @@ impl fmt::Display for Puzzle {
     }
 }
 
+/// Longest optimal solution over all solvable boards and goals, by board side
+/// length. Only known for small boards.
+const GODS_NUMBERS: [(usize, usize); 3] = [(2, 6), (3, 31), (4, 80)];
+
+/// The most moves any solvable board of side `side` needs, if known.
+pub fn gods_number(side: usize) -> Option<usize> {
+    GODS_NUMBERS
+        .iter()
+        .find(|&&(known_side, _)| known_side == side)
+        .map(|&(_, moves)| moves)
+}
+
 impl Puzzle {
+    /// Number of rows and columns.
+    pub const SIDE: usize = 3;
+
     pub fn new(board: [[u8; 3]; 3]) -> Self {
         Puzzle { board }
     }
 
+    /// Row and column of the cell at `index`, counting row by row.
+    pub const fn index_to_coord(index: usize) -> (usize, usize) {
+        (index / Self::SIDE, index % Self::SIDE)
+    }
+
+    /// Index of cell `(i, j)` counting row by row, the inverse of
+    /// `index_to_coord`.
+    pub const fn coord_to_index(i: usize, j: usize) -> usize {
+        i * Self::SIDE + j
+    }
+
     pub fn from_random() -> Self {
-        let mut rnd = rand::thread_rng();
+        Self::from_rng(&mut rand::thread_rng())
+    }
+
+    /// Random board drawn from `rnd`, a seeded generator gives the same board
+    /// every time.
+    pub fn from_rng<R: Rng>(rnd: &mut R) -> Self {
         let mut numbers = (1..=8).collect::<Vec<u8>>();
         let mut board = [[0; 3]; 3];
 
         let zero_pos = rnd.gen_range(0..9);
-        board[zero_pos / 3][zero_pos % 3] = 0;
-        for i in 0..9 {
-            if i != zero_pos {
-                board[i / 3][i % 3] = numbers.remove(rnd.gen_range(0..numbers.len()));
+        for index in 0..9 {
+            if index != zero_pos {
+                let (i, j) = Self::index_to_coord(index);
+                board[i][j] = numbers.remove(rnd.gen_range(0..numbers.len()));
             }
         }
 
@@ -58,34 +113,239 @@ impl Puzzle {
         None
     }
 
+    /// Position of the blank, every valid board has exactly one.
+    pub fn blank(&self) -> (usize, usize) {
+        self.find_zero().expect("puzzle has no blank")
+    }
+
+    /// Whether the blank can move in `direction` without leaving the board.
+    pub fn can_move(&self, direction: Direction) -> bool {
+        match (self.find_zero(), direction) {
+            (Some((i, _)), Direction::Up) => i > 0,
+            (Some((i, _)), Direction::Down) => i < Self::SIDE - 1,
+            (Some((_, j)), Direction::Left) => j > 0,
+            (Some((_, j)), Direction::Right) => j < Self::SIDE - 1,
+            (None, _) => false,
+        }
+    }
+
     pub fn move_zero(&self, direction: Direction) -> Option<Puzzle> {
-        if let Some((i, j)) = self.find_zero() {
-            let new_board = self.board;
-            let (new_i, new_j) = match direction {
-                Direction::Up => (i as i32 - 1, j as i32),
-                Direction::Down => (i as i32 + 1, j as i32),
-                Direction::Left => (i as i32, j as i32 - 1),
-                Direction::Right => (i as i32, j as i32 + 1),
-            };
-            if new_i >= 0 && new_i < 3 && new_j >= 0 && new_j < 3 {
-                let mut new_board = new_board;
-                new_board[i][j] = new_board[new_i as usize][new_j as usize];
-                new_board[new_i as usize][new_j as usize] = 0;
-                Some(Puzzle::new(new_board))
-            } else {
-                None
-            }
-        } else {
-            None
+        if !self.can_move(direction) {
+            return None;
         }
+
+        let (i, j) = self.find_zero()?;
+        let (new_i, new_j) = match direction {
+            Direction::Up => (i - 1, j),
+            Direction::Down => (i + 1, j),
+            Direction::Left => (i, j - 1),
+            Direction::Right => (i, j + 1),
+        };
+        let mut new_board = self.board;
+        new_board[i][j] = new_board[new_i][new_j];
+        new_board[new_i][new_j] = 0;
+        Some(Puzzle::new(new_board))
+    }
+
+    /// Moves the blank into cell `(i, j)`, which has to be orthogonally
+    /// adjacent to it.
+    pub fn swap_blank_with(&self, i: usize, j: usize) -> Option<Puzzle> {
+        let (blank_i, blank_j) = self.find_zero()?;
+        let direction = match (i as isize - blank_i as isize, j as isize - blank_j as isize) {
+            (-1, 0) => Direction::Up,
+            (1, 0) => Direction::Down,
+            (0, -1) => Direction::Left,
+            (0, 1) => Direction::Right,
+            _ => return None,
+        };
+        self.move_zero(direction)
     }
 
     pub fn get_value(&self, i: usize, j: usize) -> u8 {
         self.board[i][j]
     }
+
+    /// The tiles read row by row, four bits each, the first one highest.
+    /// Distinct boards pack to distinct values, which gives them a fixed
+    /// order.
+    pub fn packed(&self) -> u64 {
+        self.board
+            .iter()
+            .flatten()
+            .fold(0, |packed, &value| (packed << 4) | value as u64)
+    }
+
+    /// Board whose cell `(i, j)` holds the tile of cell `source(i, j)`.
+    fn remap(&self, source: impl Fn(usize, usize) -> (usize, usize)) -> Puzzle {
+        let mut board = [[0; 3]; 3];
+        for (i, row) in board.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let (source_i, source_j) = source(i, j);
+                *cell = self.board[source_i][source_j];
+            }
+        }
+        Puzzle::new(board)
+    }
+
+    /// The board turned a quarter clockwise. Tiles keep their numbers, so
+    /// transforming a board and its goal alike keeps the distance between
+    /// them.
+    pub fn rotate90(&self) -> Puzzle {
+        self.remap(|i, j| (2 - j, i))
+    }
+
+    pub fn rotate180(&self) -> Puzzle {
+        self.remap(|i, j| (2 - i, 2 - j))
+    }
+
+    pub fn rotate270(&self) -> Puzzle {
+        self.remap(|i, j| (j, 2 - i))
+    }
+
+    /// The board mirrored left to right.
+    pub fn reflect_horizontal(&self) -> Puzzle {
+        self.remap(|i, j| (i, 2 - j))
+    }
+
+    /// The board mirrored top to bottom.
+    pub fn reflect_vertical(&self) -> Puzzle {
+        self.remap(|i, j| (2 - i, j))
+    }
+
+    /// The blank move that turns this board into `next`, if they are one
+    /// move apart.
+    pub fn direction_to(&self, next: &Puzzle) -> Option<Direction> {
+        Direction::all()
+            .into_iter()
+            .find(|&direction| self.move_zero(direction).as_ref() == Some(next))
+    }
+
+    /// Pairs of tiles, blank excluded, that appear in the opposite order of
+    /// their values when reading the board row by row.
+    fn inversions(&self) -> usize {
+        let tiles = self
+            .board
+            .iter()
+            .flatten()
+            .filter(|&&value| value != 0)
+            .collect::<Vec<_>>();
+
+        (0..tiles.len())
+            .map(|i| {
+                tiles[i + 1..]
+                    .iter()
+                    .filter(|&&other| other < tiles[i])
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Permutation parity of the board, `true` when odd. Two boards of the
+    /// same size reach each other exactly when their parities match.
+    ///
+    /// On a board of odd width this is the parity of the inversion count,
+    /// which no move changes: a vertical move takes a tile past an even
+    /// number of others. On even widths it takes the tile past an odd
+    /// number and flips the inversion parity, so the row of the blank,
+    /// which flips along with it, is added in.
+    pub fn parity(&self) -> bool {
+        let blank_row = match Self::SIDE % 2 {
+            0 => self.blank().0,
+            _ => 0,
+        };
+        (self.inversions() + blank_row) % 2 == 1
+    }
+
+    /// The board with the contents of cells `a` and `b` exchanged. Swapping
+    /// two tiles, neither of them the blank, flips `parity`, which no
+    /// sequence of moves does.
+    pub fn swap_cells(&self, a: (usize, usize), b: (usize, usize)) -> Puzzle {
+        let mut board = self.board;
+        board[a.0][a.1] = self.board[b.0][b.1];
+        board[b.0][b.1] = self.board[a.0][a.1];
+        Puzzle { board }
+    }
+
+    /// Whether the numbered tiles of both boards read the same row by row,
+    /// the blank skipped wherever it is. Unlike `==`, a board with its blank
+    /// moved left or right is still equal, and so is a goal with the blank
+    /// in another corner. Tiles alone cannot all keep their cells while the
+    /// blank moves, so their order is what is compared.
+    pub fn eq_ignore_blank(&self, other: &Puzzle) -> bool {
+        let tiles = |puzzle: &Puzzle| {
+            puzzle
+                .board
+                .into_iter()
+                .flatten()
+                .filter(|&value| value != 0)
+        };
+        tiles(self).eq(tiles(other))
+    }
+
+    /// Whether `goal` can be reached from this board.
+    pub fn is_solvable_to(&self, goal: &Puzzle) -> bool {
+        self.parity() == goal.parity()
+    }
+
+    /// Cells whose value differs from `goal`, the blank only counted when
+    /// `include_blank` is set.
+    pub fn misplaced_tiles(&self, goal: &Puzzle, include_blank: bool) -> i32 {
+        let mut count = 0;
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let value = self.board[i][j];
+                if value != goal.board[i][j] && (include_blank || value != 0) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Position of tile `value` on the board.
+    pub fn position_of(&self, value: u8) -> Option<(usize, usize)> {
+        (0..9)
+            .map(Self::index_to_coord)
+            .find(|&(i, j)| self.board[i][j] == value)
+    }
+
+    /// Sum over the tiles, blank excluded, of how many rows and columns each
+    /// one is away from its place in `goal`.
+    pub fn manhattan_to(&self, goal: &Puzzle) -> i32 {
+        self.manhattan_per_tile(goal).iter().sum()
+    }
+
+    /// The terms of `manhattan_to`: how many rows and columns each tile is
+    /// away from its place in `goal`, indexed by tile value. The blank, at
+    /// index 0, is always 0.
+    pub fn manhattan_per_tile(&self, goal: &Puzzle) -> [i32; 9] {
+        let mut distances = [0; 9];
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let value = self.board[i][j];
+                if value == 0 {
+                    continue;
+                }
+                if let Some((goal_i, goal_j)) = goal.position_of(value) {
+                    distances[value as usize] = (i.abs_diff(goal_i) + j.abs_diff(goal_j)) as i32;
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Distribution of optimal solution lengths over all boards that can
+    /// reach this one (index = number of moves, value = count).
+    pub fn distance_histogram(&self) -> Vec<usize> {
+        SolutionMap::new(*self).distance_histogram()
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Direction {
     Up,
     Down,
@@ -111,11 +371,102 @@ impl Direction {
             Direction::Right => Direction::Left,
         }
     }
+
+    /// The direction spelled out, e.g. `"Up"`, where `Display` writes its
+    /// letter.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Direction::Up => "Up",
+            Direction::Down => "Down",
+            Direction::Left => "Left",
+            Direction::Right => "Right",
+        }
+    }
+
+    /// The direction written as `letter` by `Display`, in either case.
+    pub fn from_char(letter: char) -> Option<Direction> {
+        match letter.to_ascii_uppercase() {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
+/// The first letter of the direction the blank moves in.
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+        };
+        f.write_str(letter)
+    }
 }
 
 pub trait Heuristic {
     fn new() -> Self;
+    /// Human-readable name, used by the UI and in exported results.
+    fn name(&self) -> &'static str;
     fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32;
+    /// Cost of the move `from -> to`, made by moving the blank in `direction`.
+    /// Every move costs 1 by default.
+    fn cost(&self, _from: &Puzzle, _to: &Puzzle, _direction: Direction) -> i32 {
+        1
+    }
+    /// Never overestimates the remaining cost, so A* finds optimal paths.
+    /// Heuristics make no such promise unless they say so.
+    fn is_admissible(&self) -> bool {
+        false
+    }
+    /// Never drops by more than the cost of a move, so no board needs to be
+    /// reopened once closed.
+    fn is_consistent(&self) -> bool {
+        false
+    }
+    /// Called when `node` is closed at cost `g`, stateful heuristics can
+    /// update their estimates here.
+    fn on_expand(&mut self, _node: &Puzzle, _g: i32) {}
+}
+
+/// How far a running search has come, reported after every expansion.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchProgress {
+    /// Boards closed so far.
+    pub expanded: usize,
+    /// Boards pushed to the open set so far, initial ones included.
+    pub generated: usize,
+    /// Most boards the open set has held at once.
+    pub max_open: usize,
+    /// Highest f among the closed boards. With an admissible heuristic and
+    /// default weights it never exceeds the cost of the solution, and climbs
+    /// towards it as the search goes on.
+    pub best_f: f64,
+}
+
+impl SearchProgress {
+    /// Share of `limit` expansions done, for searches that stop there.
+    pub fn of_limit(&self, limit: usize) -> f64 {
+        match limit {
+            0 => 1.0,
+            _ => (self.expanded as f64 / limit as f64).min(1.0),
+        }
+    }
+
+    /// Share of `best_f` in `max_cost`, a known bound on the solution cost
+    /// such as `gods_number`. Only a rough estimate: the solution is usually
+    /// cheaper than the bound, and `best_f` can stay put for many expansions
+    /// before climbing again.
+    pub fn of_cost(&self, max_cost: f64) -> f64 {
+        match max_cost > 0.0 {
+            true => (self.best_f / max_cost).clamp(0.0, 1.0),
+            false => 1.0,
+        }
+    }
 }
 
 pub trait SearchTree {
@@ -123,6 +474,45 @@ pub trait SearchTree {
     fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)>;
     fn set(&mut self, key: Puzzle, value: (Puzzle, i32));
     fn step_callback(&mut self, _current: &Puzzle, _next: (&Puzzle, bool), _open_set: &OpenSet) {}
+    /// Called once a board is closed, after `set`.
+    fn on_progress(&mut self, _progress: &SearchProgress) {}
+    /// Checked before every expansion, the search gives up without a
+    /// solution once it returns true.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Coefficients of f = g * `g` + h * `h`, the value open boards are ranked
+/// by. `(1, 1)` is plain A*, `(1, 0)` ignores the heuristic (Dijkstra, BFS
+/// on unit costs) and `(0, 1)` ignores the path so far (greedy best-first).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostWeights {
+    pub g: f64,
+    pub h: f64,
+}
+
+impl CostWeights {
+    pub fn new(g: f64, h: f64) -> Self {
+        CostWeights { g, h }
+    }
+
+    fn f(&self, g: i32, h: i32) -> f64 {
+        self.g * g as f64 + self.h * h as f64
+    }
+
+    /// Whether an admissible, consistent heuristic still finds shortest
+    /// paths: scaling f by `g` leaves h scaled by `h / g`, which must not
+    /// overestimate.
+    pub fn keeps_optimality(&self) -> bool {
+        self.g > 0.0 && self.h <= self.g
+    }
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        CostWeights::new(1.0, 1.0)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -131,11 +521,12 @@ struct BinaryHeapNode {
     parent: Puzzle,
     g: i32,
     h: i32,
+    f: f64,
 }
 
 impl PartialEq for BinaryHeapNode {
     fn eq(&self, other: &Self) -> bool {
-        (self.g + self.h) == (other.g + other.h)
+        self.f.total_cmp(&other.f).is_eq()
     }
 }
 
@@ -149,20 +540,51 @@ impl Eq for BinaryHeapNode {}
 
 impl Ord for BinaryHeapNode {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (other.g + other.h).cmp(&(self.g + self.h))
+        other.f.total_cmp(&self.f)
     }
 }
 
 pub struct OpenSet {
     set: BinaryHeap<BinaryHeapNode>,
     map: HashMap<Puzzle, (Puzzle, i32)>,
+    max_len: Option<usize>,
 }
 
 impl OpenSet {
-    fn new() -> Self {
+    fn new(max_len: Option<usize>) -> Self {
         OpenSet {
             set: BinaryHeap::new(),
             map: HashMap::new(),
+            max_len,
+        }
+    }
+
+    /// Whether `node` is the cheapest path to its board, not an entry left
+    /// behind in the heap when a cheaper one was found.
+    fn is_current(&self, node: &BinaryHeapNode) -> bool {
+        self.map.get(&node.puzzle).map(|real| real.1) == Some(node.g)
+    }
+
+    /// Forgets the board with the highest f. Its heap entry turns stale and
+    /// is skipped, the heap is compacted once stale entries outnumber the
+    /// live ones.
+    fn drop_worst(&mut self) {
+        // The heap is ordered for popping the lowest f, its minimum is the
+        // highest f.
+        let worst = self
+            .set
+            .iter()
+            .filter(|node| self.is_current(node))
+            .min()
+            .map(|node| node.puzzle);
+        if let Some(worst) = worst {
+            self.map.remove(&worst);
+        }
+
+        if self.set.len() > 2 * self.map.len() {
+            let map = &self.map;
+            self.set
+                .retain(|node| map.get(&node.puzzle).map(|real| real.1) == Some(node.g));
         }
     }
 
@@ -173,6 +595,10 @@ impl OpenSet {
             self.map.insert(node.puzzle, (node.parent, node.g));
             self.set.push(node);
         }
+
+        if self.max_len.is_some_and(|max_len| self.map.len() > max_len) {
+            self.drop_worst();
+        }
     }
 
     fn pop(&mut self) -> Option<BinaryHeapNode> {
@@ -185,6 +611,7 @@ impl OpenSet {
                         parent: real.0,
                         g: real.1,
                         h: node.h, // This value is not used outside of the binary heap
+                        f: node.f,
                     });
                 }
             } else {
@@ -193,9 +620,38 @@ impl OpenSet {
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Frontier boards with the f the heap ranks them by, weighted as
+    /// `CostWeights` says, lowest f first. Heap entries superseded by a
+    /// cheaper path are skipped.
+    ///
+    /// This collects and sorts the whole frontier, O(n log n) per call.
+    pub fn iter_by_priority(&self) -> impl Iterator<Item = (&Puzzle, f64)> {
+        let mut entries = self
+            .set
+            .iter()
+            .filter(|node| self.is_current(node))
+            .map(|node| (&node.puzzle, node.f))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        entries.into_iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
     pub fn iter(&self) -> std::collections::hash_map::Iter<Puzzle, (Puzzle, i32)> {
         self.map.iter()
     }
+
+    /// Parent and g of `key` on the cheapest path found to it so far.
+    pub fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+        self.map.get(key).copied()
+    }
 }
 
 pub fn solve_from_initial<S: SearchTree, H: Heuristic>(
@@ -203,43 +659,943 @@ pub fn solve_from_initial<S: SearchTree, H: Heuristic>(
     goal: Puzzle,
     closed_set: &mut S,
 ) {
-    let mut open_set = OpenSet::new();
-    let mut h_estimator = H::new();
+    solve_from_initials::<S, H>(&[initial], goal, closed_set);
+}
 
-    open_set.push(BinaryHeapNode {
-        puzzle: initial,
-        parent: initial,
-        g: 0,
-        h: 0,
-    });
+/// Same as `solve_from_initial`, with the heuristic estimating the distance
+/// to `reference` instead of `goal`, `None` meaning `goal` itself. A
+/// reference other than the goal misleads the search: the estimates no
+/// longer say how far the goal is, so even an admissible heuristic can
+/// overestimate, and the search strays towards `reference` first. That
+/// shows how much A* owes to a heuristic that fits its problem. The search
+/// still ends at `goal` and returns the path to it, only not always the
+/// shortest one.
+pub fn solve_from_initial_estimating<S: SearchTree, H: Heuristic>(
+    initial: Puzzle,
+    goal: Puzzle,
+    reference: Option<Puzzle>,
+    closed_set: &mut S,
+) -> Option<Vec<Puzzle>> {
+    let goal_test = GoalTest {
+        goals: &[goal],
+        accepts: None,
+        reference: reference.as_ref(),
+        check_parity: true,
+    };
+    let (_, path) = search::<S, H>(
+        &[initial],
+        goal_test,
+        closed_set,
+        &mut H::new(),
+        Direction::all(),
+        CostWeights::default(),
+        None,
+    )?;
+    Some(path)
+}
+
+/// Same as `solve_from_initial`, stopping at the first board `goal_test`
+/// accepts instead of at `goal`, and returning the path to it. `goal` is
+/// still the board the heuristic estimates the distance to and the parity
+/// check compares with, so it should be one `goal_test` accepts. The board
+/// reached is not always the nearest accepted one, as the heuristic may
+/// overestimate the way to the others.
+pub fn solve_from_initial_until<S: SearchTree, H: Heuristic>(
+    initial: Puzzle,
+    goal: Puzzle,
+    goal_test: impl Fn(&Puzzle) -> bool,
+    closed_set: &mut S,
+) -> Option<Vec<Puzzle>> {
+    let goal_test = GoalTest {
+        goals: &[goal],
+        accepts: Some(&goal_test),
+        reference: None,
+        check_parity: true,
+    };
+    let (_, path) = search::<S, H>(
+        &[initial],
+        goal_test,
+        closed_set,
+        &mut H::new(),
+        Direction::all(),
+        CostWeights::default(),
+        None,
+    )?;
+    Some(path)
+}
+
+/// Searches from `initial` until any of `goals` is reached. Returns the index
+/// of the goal found along with the path to it. Boards are ranked by their
+/// lowest estimate over all goals, which stays admissible when the heuristic
+/// is, so the goal found is a nearest one.
+pub fn solve_to_any<S: SearchTree, H: Heuristic>(
+    initial: Puzzle,
+    goals: &[Puzzle],
+    closed_set: &mut S,
+) -> Option<(usize, Vec<Puzzle>)> {
+    let (_, path) = search::<S, H>(
+        &[initial],
+        GoalTest::any_of(goals),
+        closed_set,
+        &mut H::new(),
+        Direction::all(),
+        CostWeights::default(),
+        None,
+    )?;
+    let reached = goals.iter().position(|goal| path.last() == Some(goal))?;
+    Some((reached, path))
+}
+
+/// Multi-source search: every board in `initials` starts in the open set at
+/// g = 0. Returns the index of the initial board the solution starts from,
+/// along with the path from it to `goal`.
+pub fn solve_from_initials<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+) -> Option<(usize, Vec<Puzzle>)> {
+    solve_from_initials_in_order::<S, H>(initials, goal, closed_set, Direction::all())
+}
+
+/// Same as `solve_from_initials`, searching with `heuristic` instead of a
+/// fresh `H::new()`. Whatever a stateful heuristic learns on the way is left
+/// in it for the caller to look at.
+pub fn solve_from_initials_with<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+    heuristic: &mut H,
+) -> Option<(usize, Vec<Puzzle>)> {
+    search(
+        initials,
+        GoalTest::any_of(&[goal]),
+        closed_set,
+        heuristic,
+        Direction::all(),
+        CostWeights::default(),
+        None,
+    )
+}
+
+/// Same as `solve_from_initials`, generating the neighbours of each board by
+/// moving the blank in `order`. The order breaks ties between equally good
+/// boards, so it changes the shape of the explored tree.
+pub fn solve_from_initials_in_order<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+    order: [Direction; 4],
+) -> Option<(usize, Vec<Puzzle>)> {
+    solve_from_initials_weighted::<S, H>(initials, goal, closed_set, order, CostWeights::default())
+}
+
+/// Same as `solve_from_initials_in_order`, ranking open boards by
+/// f = `weights.g` * g + `weights.h` * h instead of g + h.
+pub fn solve_from_initials_weighted<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+    order: [Direction; 4],
+    weights: CostWeights,
+) -> Option<(usize, Vec<Puzzle>)> {
+    solve_from_initials_bounded::<S, H>(initials, goal, closed_set, order, weights, None)
+}
+
+/// Same as `solve_from_initials_weighted`, keeping at most `max_open` boards
+/// in the open set when given. Once it is full the board with the highest f
+/// is forgotten, and is only found again through another parent. The search
+/// then is neither complete nor optimal: it may miss a reachable goal, or
+/// reach it by a longer path. A bound the open set never hits changes
+/// nothing.
+///
+/// Initial boards of the wrong parity to reach `goal` are left out, when
+/// none is left the search fails without expanding anything.
+pub fn solve_from_initials_bounded<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+    order: [Direction; 4],
+    weights: CostWeights,
+    max_open: Option<usize>,
+) -> Option<(usize, Vec<Puzzle>)> {
+    search::<S, H>(
+        initials,
+        GoalTest::any_of(&[goal]),
+        closed_set,
+        &mut H::new(),
+        order,
+        weights,
+        max_open,
+    )
+}
+
+/// Same as `solve_from_initials_bounded` without the parity check: a board
+/// that cannot reach `goal` is searched until the open set runs out, which
+/// on the 8-puzzle means expanding all 181440 boards of its parity.
+pub fn solve_from_initials_exhaustive<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal: Puzzle,
+    closed_set: &mut S,
+    order: [Direction; 4],
+    weights: CostWeights,
+    max_open: Option<usize>,
+) -> Option<(usize, Vec<Puzzle>)> {
+    let goals = [goal];
+    let goal_test = GoalTest {
+        check_parity: false,
+        ..GoalTest::any_of(&goals)
+    };
+    search::<S, H>(
+        initials,
+        goal_test,
+        closed_set,
+        &mut H::new(),
+        order,
+        weights,
+        max_open,
+    )
+}
 
+/// What ends a search. The heuristic and the parity check only ever look at
+/// `goals`, a board `accepts` takes ends the search as well when given. The
+/// heuristic estimates the distance to `reference` instead when given.
+/// Initial boards of the wrong parity for all of `goals` are left out unless
+/// `check_parity` is off.
+struct GoalTest<'a> {
+    goals: &'a [Puzzle],
+    accepts: Option<&'a dyn Fn(&Puzzle) -> bool>,
+    reference: Option<&'a Puzzle>,
+    check_parity: bool,
+}
+
+impl<'a> GoalTest<'a> {
+    fn any_of(goals: &'a [Puzzle]) -> Self {
+        GoalTest {
+            goals,
+            accepts: None,
+            reference: None,
+            check_parity: true,
+        }
+    }
+
+    fn is_met(&self, puzzle: &Puzzle) -> bool {
+        match self.accepts {
+            Some(accepts) => accepts(puzzle),
+            None => self.goals.contains(puzzle),
+        }
+    }
+}
+
+/// Searches from all of `initials` until it closes a board that meets
+/// `goal_test`, estimating and observing with `h_estimator`.
+fn search<S: SearchTree, H: Heuristic>(
+    initials: &[Puzzle],
+    goal_test: GoalTest,
+    closed_set: &mut S,
+    h_estimator: &mut H,
+    order: [Direction; 4],
+    weights: CostWeights,
+    max_open: Option<usize>,
+) -> Option<(usize, Vec<Puzzle>)> {
+    let goals = goal_test.goals;
+    let estimated_goals = match goal_test.reference {
+        Some(reference) => std::slice::from_ref(reference),
+        None => goals,
+    };
+    let mut open_set = OpenSet::new(max_open);
+
+    let reachable = |initial: &Puzzle| {
+        !goal_test.check_parity || goals.iter().any(|goal| initial.is_solvable_to(goal))
+    };
+    let mut progress = SearchProgress::default();
+    for &initial in initials.iter().filter(|initial| reachable(initial)) {
+        open_set.push(BinaryHeapNode {
+            puzzle: initial,
+            parent: initial,
+            g: 0,
+            h: 0,
+            f: 0.0,
+        });
+        progress.generated += 1;
+    }
+    progress.max_open = open_set.len();
+
+    let mut reached = None;
     while let Some(current) = open_set.pop() {
+        if closed_set.is_cancelled() {
+            break;
+        }
         closed_set.set(current.puzzle, (current.parent, current.g));
+        h_estimator.on_expand(&current.puzzle, current.g);
+        progress.expanded += 1;
+        progress.best_f = progress.best_f.max(current.f);
+        closed_set.on_progress(&progress);
 
-        if current.puzzle == goal {
+        if goal_test.is_met(&current.puzzle) {
+            reached = Some(current.puzzle);
             break;
         }
 
         let current_g = closed_set.get(&current.puzzle).unwrap().1;
 
-        for direction in Direction::all() {
+        for direction in order {
             if let Some(next) = current.puzzle.move_zero(direction) {
                 if closed_set.get(&next).is_some() {
                     continue;
                 }
                 
-                let g = current_g + 1;
-                let h = h_estimator.estimate_h(&next, &goal);
+                let g = current_g + h_estimator.cost(&current.puzzle, &next, direction);
+                let h = estimated_goals
+                    .iter()
+                    .map(|goal| h_estimator.estimate_h(&next, goal))
+                    .min()
+                    .unwrap_or(0);
 
                 open_set.push(BinaryHeapNode {
                     puzzle: next,
                     parent: current.puzzle,
                     g,
                     h,
+                    f: weights.f(g, h),
                 });
+                progress.generated += 1;
+                progress.max_open = progress.max_open.max(open_set.len());
                 closed_set.step_callback(&current.puzzle, (&next, false), &open_set);
                 // TODO: remove this false
             }
         }
     }
+
+    let mut current = reached?;
+    let mut path = vec![current];
+
+    while let Some((parent, _)) = closed_set.get(&current) {
+        if parent == current {
+            break;
+        }
+        path.push(parent);
+        current = parent;
+    }
+    path.reverse();
+
+    let source = initials.iter().position(|initial| *initial == current)?;
+    Some((source, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    /// Manhattan distance that remembers the largest estimate it gave, and
+    /// the expansions it is told about.
+    #[derive(Default)]
+    struct ObservingHeuristic {
+        max_h: i32,
+        expanded: usize,
+        deepest: i32,
+    }
+
+    impl Heuristic for ObservingHeuristic {
+        fn new() -> Self {
+            ObservingHeuristic::default()
+        }
+
+        fn name(&self) -> &'static str {
+            "Observing"
+        }
+
+        fn estimate_h(&mut self, current: &Puzzle, goal: &Puzzle) -> i32 {
+            let h = ManhattanHeuristic::new().estimate_h(current, goal);
+            self.max_h = self.max_h.max(h);
+            h
+        }
+
+        fn on_expand(&mut self, _node: &Puzzle, g: i32) {
+            self.expanded += 1;
+            self.deepest = self.deepest.max(g);
+        }
+    }
+
+    /// Uniform-cost search where moving the blank left costs twice as much.
+    struct LeftCostsDouble {}
+
+    impl Heuristic for LeftCostsDouble {
+        fn new() -> Self {
+            LeftCostsDouble {}
+        }
+
+        fn name(&self) -> &'static str {
+            "Left costs double"
+        }
+
+        fn estimate_h(&mut self, _current: &Puzzle, _goal: &Puzzle) -> i32 {
+            0
+        }
+
+        fn cost(&self, _from: &Puzzle, _to: &Puzzle, direction: Direction) -> i32 {
+            if direction == Direction::Left {
+                2
+            } else {
+                1
+            }
+        }
+    }
+
+    struct ClosedSet(HashMap<Puzzle, (Puzzle, i32)>, Puzzle);
+
+    impl SearchTree for ClosedSet {
+        fn goal_reached(&self) -> bool {
+            self.0.contains_key(&self.1)
+        }
+        fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+            self.0.get(key).copied()
+        }
+        fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+            self.0.insert(key, value);
+        }
+    }
+
+    #[test]
+    fn heuristic_observes_every_expansion() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let mut heuristic = ObservingHeuristic::new();
+        let (_, path) =
+            solve_from_initials_with(&[initial], goal(), &mut closed_set, &mut heuristic).unwrap();
+
+        assert_eq!(heuristic.expanded, closed_set.0.len());
+        assert_eq!(heuristic.deepest as usize, path.len() - 1);
+        // Every neighbour of a closed board is estimated once, but for the
+        // initial board, which is closed before anything else.
+        let estimated = closed_set
+            .0
+            .keys()
+            .flat_map(|puzzle| Direction::all().map(|direction| puzzle.move_zero(direction)))
+            .flatten()
+            .filter(|puzzle| *puzzle != initial);
+        let max_h = estimated
+            .map(|puzzle| ManhattanHeuristic::new().estimate_h(&puzzle, &goal()))
+            .max();
+        assert_eq!(Some(heuristic.max_h), max_h);
+    }
+
+    #[test]
+    fn move_costs_steer_the_search() {
+        let initial = Puzzle::new([[1, 5, 2], [0, 4, 3], [8, 7, 6]]);
+        let cost = |path: &[Puzzle]| -> i32 {
+            path.windows(2)
+                .map(|pair| {
+                    let direction = pair[0].direction_to(&pair[1]).unwrap();
+                    LeftCostsDouble {}.cost(&pair[0], &pair[1], direction)
+                })
+                .sum()
+        };
+
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let (_, unit_path) =
+            solve_from_initials::<_, BfsHeuristic>(&[initial], goal(), &mut closed_set).unwrap();
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let (_, costed_path) =
+            solve_from_initials::<_, LeftCostsDouble>(&[initial], goal(), &mut closed_set).unwrap();
+
+        // Both are 11 moves, the costed search makes one move left fewer.
+        assert_eq!(unit_path.len(), costed_path.len());
+        assert_ne!(unit_path, costed_path);
+        assert_eq!((cost(&unit_path), cost(&costed_path)), (14, 13));
+        assert_eq!(closed_set.get(&goal()).unwrap().1, 13);
+    }
+
+    #[test]
+    fn wrong_parity_fails_without_expanding() {
+        // Swapping two tiles flips the permutation parity.
+        let swapped = Puzzle::new([[2, 1, 3], [8, 0, 4], [7, 6, 5]]);
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let found =
+            solve_from_initials::<_, ManhattanHeuristic>(&[swapped], goal(), &mut closed_set);
+
+        assert_eq!(found, None);
+        assert!(closed_set.0.is_empty());
+
+        // Only the reachable one of several initial boards is searched.
+        let one_move = goal().move_zero(Direction::Up).unwrap();
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let (source, path) = solve_from_initials::<_, ManhattanHeuristic>(
+            &[swapped, one_move],
+            goal(),
+            &mut closed_set,
+        )
+        .unwrap();
+        assert_eq!((source, path.len()), (1, 2));
+        assert!(!closed_set.0.contains_key(&swapped));
+    }
+
+    #[test]
+    fn parity_check_can_be_skipped() {
+        let swapped = Puzzle::new([[2, 1, 3], [8, 0, 4], [7, 6, 5]]);
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let found = solve_from_initials_exhaustive::<_, BfsHeuristic>(
+            &[swapped],
+            goal(),
+            &mut closed_set,
+            Direction::all(),
+            CostWeights::default(),
+            None,
+        );
+
+        assert_eq!(found, None);
+        // Every board of the other parity, 9! / 2.
+        assert_eq!(closed_set.0.len(), 181440);
+    }
+
+    /// Remembers the largest open set it was shown.
+    struct WatchOpenSet {
+        closed_set: ClosedSet,
+        largest: usize,
+    }
+
+    impl SearchTree for WatchOpenSet {
+        fn goal_reached(&self) -> bool {
+            self.closed_set.goal_reached()
+        }
+        fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+            self.closed_set.get(key)
+        }
+        fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+            self.closed_set.set(key, value);
+        }
+        fn step_callback(&mut self, _current: &Puzzle, _next: (&Puzzle, bool), open_set: &OpenSet) {
+            self.largest = self.largest.max(open_set.len());
+        }
+    }
+
+    #[test]
+    fn nearest_of_several_initials_is_the_source() {
+        let near = goal().scrambled("U L").unwrap();
+        let far = goal().scrambled("D R U L U").unwrap();
+
+        for (initials, expected) in [([far, near], 1), ([near, far], 0)] {
+            let mut closed_set = ClosedSet(HashMap::new(), goal());
+            let (source, path) =
+                solve_from_initials::<_, ManhattanHeuristic>(&initials, goal(), &mut closed_set)
+                    .unwrap();
+            assert_eq!(source, expected);
+            assert_eq!(path.first(), Some(&near));
+            assert_eq!(path.last(), Some(&goal()));
+            assert_eq!(path.len(), 3);
+        }
+    }
+
+    #[test]
+    fn nearest_of_several_goals_is_found() {
+        let near = goal();
+        let far = goal().scrambled("D R U L").unwrap();
+        let initial = goal().scrambled("U L").unwrap();
+
+        for (goals, expected) in [([far, near], 1), ([near, far], 0)] {
+            let mut closed_set = ClosedSet(HashMap::new(), near);
+            let (reached, path) =
+                solve_to_any::<_, ManhattanHeuristic>(initial, &goals, &mut closed_set).unwrap();
+            assert_eq!(reached, expected);
+            assert_eq!(path.first(), Some(&initial));
+            assert_eq!(path.last(), Some(&near));
+            assert_eq!(path.len(), 3);
+        }
+    }
+
+    #[test]
+    fn goal_test_can_ignore_the_blank() {
+        let blank_anywhere = |puzzle: &Puzzle| puzzle.eq_ignore_blank(&goal());
+        let initial = goal().scrambled("L U R").unwrap();
+
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let path = solve_from_initial_until::<_, BfsHeuristic>(
+            initial,
+            goal(),
+            blank_anywhere,
+            &mut closed_set,
+        )
+        .unwrap();
+        assert_eq!(path.first(), Some(&initial));
+        assert_eq!(path.last(), Some(&goal().scrambled("L").unwrap()));
+        assert_eq!(path.len(), 3);
+
+        let mut closed_set = ClosedSet(HashMap::new(), goal());
+        let (_, exact) =
+            solve_from_initials::<_, BfsHeuristic>(&[initial], goal(), &mut closed_set).unwrap();
+        assert_eq!(exact.len(), 4);
+    }
+
+    #[test]
+    fn wrong_reference_goal_misleads_the_search() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let mut honest = ClosedSet(HashMap::new(), goal());
+        let path = solve_from_initial_estimating::<_, ManhattanHeuristic>(
+            initial,
+            goal(),
+            None,
+            &mut honest,
+        )
+        .unwrap();
+        assert_eq!(path.len(), 6);
+
+        // The same parity as the goal, far from it.
+        let reference = Puzzle::new([[5, 6, 7], [4, 0, 8], [3, 2, 1]]);
+        let mut misled = ClosedSet(HashMap::new(), goal());
+        let path = solve_from_initial_estimating::<_, ManhattanHeuristic>(
+            initial,
+            goal(),
+            Some(reference),
+            &mut misled,
+        )
+        .unwrap();
+        assert_eq!(path.last(), Some(&goal()));
+        assert!(
+            misled.0.len() > 10 * honest.0.len(),
+            "{} boards expanded against {}",
+            misled.0.len(),
+            honest.0.len()
+        );
+    }
+
+    #[test]
+    fn frontier_is_listed_by_weighted_f() {
+        let weights = CostWeights::new(2.0, 1.0);
+        let node = |puzzle: Puzzle, g: i32, h: i32| BinaryHeapNode {
+            puzzle,
+            parent: goal(),
+            g,
+            h,
+            f: weights.f(g, h),
+        };
+        let [up, down, left, right] =
+            Direction::all().map(|direction| goal().move_zero(direction).unwrap());
+
+        let mut open_set = OpenSet::new(None);
+        // By g + h `up` would come before `left` and `down`, by the
+        // weighted f it comes last.
+        open_set.push(node(up, 3, 1));
+        open_set.push(node(down, 1, 4));
+        open_set.push(node(left, 6, 0));
+        open_set.push(node(right, 2, 1));
+        // A cheaper path to `left` leaves its first entry stale.
+        open_set.push(node(left, 0, 4));
+
+        let order = open_set.iter_by_priority().collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            vec![(&left, 4.0), (&right, 5.0), (&down, 6.0), (&up, 7.0)]
+        );
+    }
+
+    #[test]
+    fn bounded_open_set_stays_within_bound() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let mut tree = WatchOpenSet {
+            closed_set: ClosedSet(HashMap::new(), goal()),
+            largest: 0,
+        };
+        solve_from_initials_bounded::<_, BfsHeuristic>(
+            &[initial],
+            goal(),
+            &mut tree,
+            Direction::all(),
+            CostWeights::default(),
+            Some(4),
+        );
+
+        assert!(tree.largest <= 4);
+    }
+
+    /// Keeps every progress report it gets.
+    struct WatchProgress {
+        closed_set: ClosedSet,
+        reports: Vec<SearchProgress>,
+    }
+
+    impl SearchTree for WatchProgress {
+        fn goal_reached(&self) -> bool {
+            self.closed_set.goal_reached()
+        }
+        fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+            self.closed_set.get(key)
+        }
+        fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+            self.closed_set.set(key, value);
+        }
+        fn on_progress(&mut self, progress: &SearchProgress) {
+            self.reports.push(*progress);
+        }
+    }
+
+    #[test]
+    fn best_f_climbs_to_the_solution_cost() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let mut tree = WatchProgress {
+            closed_set: ClosedSet(HashMap::new(), goal()),
+            reports: Vec::new(),
+        };
+        let (_, path) =
+            solve_from_initials::<_, ManhattanHeuristic>(&[initial], goal(), &mut tree).unwrap();
+
+        assert_eq!(tree.reports.len(), tree.closed_set.0.len());
+        for (i, pair) in tree.reports.windows(2).enumerate() {
+            assert_eq!(pair[0].expanded, i + 1);
+            assert!(pair[0].best_f <= pair[1].best_f);
+        }
+        let last = tree.reports.last().unwrap();
+        assert_eq!(last.best_f, (path.len() - 1) as f64);
+        assert_eq!(last.of_cost(last.best_f * 2.0), 0.5);
+        assert_eq!(last.of_limit(last.expanded), 1.0);
+    }
+
+    /// Cancels the search once it has closed `limit` boards.
+    struct CancelAfter {
+        closed_set: ClosedSet,
+        limit: usize,
+    }
+
+    impl SearchTree for CancelAfter {
+        fn goal_reached(&self) -> bool {
+            self.closed_set.goal_reached()
+        }
+        fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+            self.closed_set.get(key)
+        }
+        fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+            self.closed_set.set(key, value);
+        }
+        fn is_cancelled(&self) -> bool {
+            self.closed_set.0.len() >= self.limit
+        }
+    }
+
+    #[test]
+    fn cancelled_search_stops_expanding() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let mut tree = CancelAfter {
+            closed_set: ClosedSet(HashMap::new(), goal()),
+            limit: 3,
+        };
+        let solution = solve_from_initials::<_, BfsHeuristic>(&[initial], goal(), &mut tree);
+
+        assert_eq!(solution, None);
+        assert_eq!(tree.closed_set.0.len(), 3);
+    }
+
+    #[test]
+    fn index_and_coord_convert_both_ways() {
+        let cases = [
+            (0, (0, 0)),
+            (2, (0, 2)),
+            (3, (1, 0)),
+            (4, (1, 1)),
+            (8, (2, 2)),
+        ];
+        for (index, (i, j)) in cases {
+            assert_eq!(Puzzle::index_to_coord(index), (i, j));
+            assert_eq!(Puzzle::coord_to_index(i, j), index);
+        }
+
+        let puzzle = goal();
+        for index in 0..9 {
+            let (i, j) = Puzzle::index_to_coord(index);
+            assert_eq!(puzzle.position_of(puzzle.get_value(i, j)), Some((i, j)));
+        }
+    }
+
+    #[test]
+    fn blank_stays_on_the_board() {
+        // Up, down, left and right for the blank in each cell, row by row.
+        let expected = [
+            [false, true, false, true],
+            [false, true, true, true],
+            [false, true, true, false],
+            [true, true, false, true],
+            [true, true, true, true],
+            [true, true, true, false],
+            [true, false, false, true],
+            [true, false, true, true],
+            [true, false, true, false],
+        ];
+        for (index, expected) in expected.into_iter().enumerate() {
+            let mut cells = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+            cells.swap(index, 8);
+            let puzzle = Puzzle::try_from(cells).unwrap();
+            assert_eq!(puzzle.blank(), Puzzle::index_to_coord(index));
+            for (direction, expected) in Direction::all().into_iter().zip(expected) {
+                assert_eq!(puzzle.can_move(direction), expected);
+                assert_eq!(puzzle.move_zero(direction).is_some(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn blank_swaps_with_adjacent_cells_only() {
+        let puzzle = goal();
+        for (i, j) in [(0, 1), (2, 1), (1, 0), (1, 2)] {
+            let swapped = puzzle.swap_blank_with(i, j).unwrap();
+            assert_eq!(swapped.blank(), (i, j));
+            assert_eq!(swapped.get_value(1, 1), puzzle.get_value(i, j));
+        }
+
+        // Diagonal neighbours and the blank's own cell.
+        for (i, j) in [(0, 0), (0, 2), (2, 0), (2, 2), (1, 1)] {
+            assert_eq!(puzzle.swap_blank_with(i, j), None);
+        }
+
+        // Two cells away, in line with the blank.
+        let corner = Puzzle::new([[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(corner.swap_blank_with(0, 2), None);
+        assert_eq!(corner.swap_blank_with(2, 0), None);
+        assert_eq!(corner.swap_blank_with(2, 2), None);
+    }
+
+    #[test]
+    fn gods_number_matches_longest_solution() {
+        // With the blank in the center the longest solution is 30 moves, the
+        // bound is only reached for a goal with the blank in a corner.
+        let longest = GoalPreset::RowMajor.puzzle().distance_histogram().len() - 1;
+        assert_eq!(gods_number(Puzzle::SIDE), Some(longest));
+        assert_eq!(gods_number(5), None);
+    }
+
+    #[test]
+    fn solvability_follows_inversion_parity() {
+        let one_move = goal().move_zero(Direction::Up).unwrap();
+        let swapped = Puzzle::new([[2, 1, 3], [8, 0, 4], [7, 6, 5]]);
+        assert!(goal().is_solvable_to(&goal()));
+        assert!(one_move.is_solvable_to(&goal()));
+        assert!(!swapped.is_solvable_to(&goal()));
+    }
+
+    #[test]
+    fn parity_tells_compatible_boards_apart() {
+        let spiral = GoalPreset::Spiral.puzzle();
+        let row_major = GoalPreset::RowMajor.puzzle();
+        // 1 2 3 8 4 7 6 5 has 7 inversions, reading order has none.
+        assert!(spiral.parity());
+        assert!(!row_major.parity());
+
+        // The two preset goals cannot reach each other, every board is
+        // compatible with exactly one of them.
+        let scrambled = row_major.scrambled("U L U L D R").unwrap();
+        let swapped = Puzzle::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let table = [
+            (spiral, spiral, true),
+            (spiral, row_major, false),
+            (scrambled, row_major, true),
+            (scrambled, spiral, false),
+            (swapped, row_major, false),
+            (swapped, spiral, true),
+        ];
+        for (board, goal, compatible) in table {
+            assert_eq!(board.parity() == goal.parity(), compatible);
+            assert_eq!(board.is_solvable_to(&goal), compatible);
+        }
+    }
+
+    #[test]
+    fn manhattan_terms_add_up() {
+        let goal = goal();
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let terms = initial.manhattan_per_tile(&goal);
+        assert_eq!(terms, [0, 1, 1, 0, 0, 0, 1, 0, 2]);
+        assert_eq!(terms.iter().sum::<i32>(), initial.manhattan_to(&goal));
+        assert_eq!(goal.manhattan_per_tile(&goal), [0; 9]);
+    }
+
+    #[test]
+    fn equality_ignoring_the_blank() {
+        let goal = goal();
+        let beside = goal.scrambled("L").unwrap();
+        let below = goal.scrambled("D").unwrap();
+
+        assert_ne!(beside, goal);
+        assert!(beside.eq_ignore_blank(&goal));
+        assert!(goal.eq_ignore_blank(&beside));
+        // A vertical move takes the tile past two others.
+        assert_ne!(below, goal);
+        assert!(!below.eq_ignore_blank(&goal));
+        assert!(goal.eq_ignore_blank(&goal));
+        assert!(!goal.eq_ignore_blank(&goal.swap_cells((0, 0), (0, 1))));
+    }
+
+    #[test]
+    fn swapping_two_tiles_flips_parity() {
+        let spiral = GoalPreset::Spiral.puzzle();
+        let swapped = spiral.swap_cells((0, 0), (2, 2));
+        assert_eq!(swapped.get_value(0, 0), 5);
+        assert_eq!(swapped.get_value(2, 2), 1);
+        assert_ne!(swapped.parity(), spiral.parity());
+        assert_eq!(swapped.swap_cells((0, 0), (2, 2)), spiral);
+
+        // Swapping the blank with a neighbour is a move, and keeps it.
+        let moved = spiral.swap_cells((1, 1), (0, 1));
+        assert_eq!(moved.parity(), spiral.parity());
+    }
+
+    #[test]
+    fn transforms_move_tiles_with_the_board() {
+        let board = Puzzle::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]]);
+
+        assert_eq!(
+            board.rotate90(),
+            Puzzle::new([[7, 4, 1], [8, 5, 2], [0, 6, 3]])
+        );
+        assert_eq!(
+            board.reflect_horizontal(),
+            Puzzle::new([[3, 2, 1], [6, 5, 4], [0, 8, 7]])
+        );
+    }
+
+    #[test]
+    fn transforms_undo_with_their_inverse() {
+        let board = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        assert_eq!(board.rotate90().rotate270(), board);
+        assert_eq!(board.rotate270().rotate90(), board);
+        assert_eq!(board.rotate180().rotate180(), board);
+        assert_eq!(board.reflect_horizontal().reflect_horizontal(), board);
+        assert_eq!(board.reflect_vertical().reflect_vertical(), board);
+        assert_eq!(board.rotate90().rotate90(), board.rotate180());
+    }
+
+    #[test]
+    fn misplaced_tiles_with_blank() {
+        let one_move = goal().move_zero(Direction::Up).unwrap();
+        assert_eq!(goal().misplaced_tiles(&goal(), true), 0);
+        assert_eq!(one_move.misplaced_tiles(&goal(), true), 2);
+    }
+
+    #[test]
+    fn misplaced_tiles_without_blank() {
+        let one_move = goal().move_zero(Direction::Up).unwrap();
+        let board = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        assert_eq!(goal().misplaced_tiles(&goal(), false), 0);
+        assert_eq!(one_move.misplaced_tiles(&goal(), false), 1);
+        assert_eq!(board.misplaced_tiles(&goal(), false), 4);
+        assert_eq!(board.misplaced_tiles(&goal(), true), 5);
+    }
+
+    #[test]
+    fn misplaced_heuristics_differ_only_by_the_blank() {
+        let boards = [
+            goal(),
+            goal().move_zero(Direction::Up).unwrap(),
+            Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]),
+            Puzzle::new([[1, 2, 3], [8, 6, 4], [7, 5, 0]]),
+        ];
+        for board in boards {
+            let with_blank = AStarHeuristic1::new().estimate_h(&board, &goal());
+            let without_blank = AStarHeuristic2::new().estimate_h(&board, &goal());
+            let blank_misplaced = board.blank() != goal().blank();
+            assert_eq!(with_blank - without_blank, blank_misplaced as i32);
+        }
+        assert!(!AStarHeuristic1::new().is_admissible());
+        assert!(AStarHeuristic2::new().is_consistent());
+    }
 }
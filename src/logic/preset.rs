@@ -0,0 +1,37 @@
+use super::Puzzle;
+
+/// Goal arrangements in common use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalPreset {
+    /// Tiles around the edge clockwise, blank in the middle.
+    Spiral,
+    /// Tiles in reading order, blank in the bottom right corner.
+    RowMajor,
+}
+
+impl GoalPreset {
+    pub const fn all() -> [GoalPreset; 2] {
+        [GoalPreset::Spiral, GoalPreset::RowMajor]
+    }
+
+    pub fn puzzle(&self) -> Puzzle {
+        match self {
+            GoalPreset::Spiral => Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]),
+            GoalPreset::RowMajor => Puzzle::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]]),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalPreset::Spiral => "spiral",
+            GoalPreset::RowMajor => "row-major",
+        }
+    }
+
+    /// The preset `goal` is, `None` for a custom arrangement.
+    pub fn classify(goal: &Puzzle) -> Option<GoalPreset> {
+        GoalPreset::all()
+            .into_iter()
+            .find(|preset| preset.puzzle() == *goal)
+    }
+}
@@ -0,0 +1,42 @@
+use super::{OpenSet, Puzzle, SearchProgress, SearchTree};
+
+/// Wraps a search tree and remembers the order boards were expanded in.
+pub struct RecordExpansions<'a, S: SearchTree> {
+    inner: &'a mut S,
+    order: Vec<Puzzle>,
+}
+
+impl<'a, S: SearchTree> RecordExpansions<'a, S> {
+    pub fn new(inner: &'a mut S) -> Self {
+        RecordExpansions {
+            inner,
+            order: Vec::new(),
+        }
+    }
+
+    pub fn into_order(self) -> Vec<Puzzle> {
+        self.order
+    }
+}
+
+impl<S: SearchTree> SearchTree for RecordExpansions<'_, S> {
+    fn goal_reached(&self) -> bool {
+        self.inner.goal_reached()
+    }
+    fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+        self.inner.get(key)
+    }
+    fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+        self.order.push(key);
+        self.inner.set(key, value);
+    }
+    fn step_callback(&mut self, current: &Puzzle, next: (&Puzzle, bool), open_set: &OpenSet) {
+        self.inner.step_callback(current, next, open_set);
+    }
+    fn on_progress(&mut self, progress: &SearchProgress) {
+        self.inner.on_progress(progress);
+    }
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use super::{Direction, Puzzle};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScrambleError {
+    /// A character that is not one of the move letters `U`, `D`, `L`, `R`.
+    InvalidMove(char),
+    /// The move at `index` would take the blank off the board.
+    IllegalMove { index: usize, direction: Direction },
+}
+
+impl fmt::Display for ScrambleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScrambleError::InvalidMove(c) => write!(f, "invalid move \"{}\"", c),
+            ScrambleError::IllegalMove { index, direction } => write!(
+                f,
+                "move {} ({}) takes the blank off the board",
+                index + 1,
+                direction
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScrambleError {}
+
+/// Boards `moves_to` visits at most before giving up. All 181,440 boards of
+/// the same parity as a 3x3 board fit, the bound only matters on larger ones.
+const MOVES_TO_MAX_BOARDS: usize = 200_000;
+
+/// Parses blank moves written as single letters, e.g. `"U L L D R"`.
+/// Whitespace and commas between the letters are optional.
+pub fn parse_moves(s: &str) -> Result<Vec<Direction>, ScrambleError> {
+    s.chars()
+        .filter(|&c| !c.is_whitespace() && c != ',')
+        .map(|c| Direction::from_char(c).ok_or(ScrambleError::InvalidMove(c)))
+        .collect()
+}
+
+/// Checks that every board of `path` is one legal blank move away from the
+/// one before it. On failure returns the index of the first illegal
+/// transition, the one from `path[index]` to `path[index + 1]`. Meant as a
+/// self-check on paths rebuilt from a search tree.
+pub fn verify_path(path: &[Puzzle]) -> Result<(), usize> {
+    match path
+        .windows(2)
+        .position(|pair| pair[0].direction_to(&pair[1]).is_none())
+    {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+impl Puzzle {
+    /// The board after moving the blank by each of `moves` in turn.
+    pub fn apply_moves(&self, moves: &[Direction]) -> Result<Puzzle, ScrambleError> {
+        moves
+            .iter()
+            .enumerate()
+            .try_fold(*self, |board, (index, &direction)| {
+                board
+                    .move_zero(direction)
+                    .ok_or(ScrambleError::IllegalMove { index, direction })
+            })
+    }
+
+    /// A shortest sequence of blank moves turning this board into `target`,
+    /// `None` when there is none.
+    ///
+    /// Boards one move apart are answered right away and boards of the
+    /// other parity without searching. Anything else takes a BFS from this
+    /// board that grows with the distance: a few boards for short hops, up
+    /// to every board of the parity, some tens of milliseconds, for the
+    /// farthest ones. It gives up after `MOVES_TO_MAX_BOARDS` boards.
+    pub fn moves_to(&self, target: &Puzzle) -> Option<Vec<Direction>> {
+        if self == target {
+            return Some(vec![]);
+        }
+        if let Some(direction) = self.direction_to(target) {
+            return Some(vec![direction]);
+        }
+        if !self.is_solvable_to(target) {
+            return None;
+        }
+
+        let mut reached_by = HashMap::from([(*self, None)]);
+        let mut queue = VecDeque::from([*self]);
+        while let Some(current) = queue.pop_front() {
+            if current == *target {
+                break;
+            }
+            if reached_by.len() >= MOVES_TO_MAX_BOARDS {
+                return None;
+            }
+            for direction in Direction::all() {
+                if let Some(next) = current.move_zero(direction) {
+                    reached_by.entry(next).or_insert_with(|| {
+                        queue.push_back(next);
+                        Some(direction)
+                    });
+                }
+            }
+        }
+
+        let mut moves = Vec::new();
+        let mut current = *target;
+        while let Some(direction) = *reached_by.get(&current)? {
+            moves.push(direction);
+            current = current.move_zero(direction.reverse())?;
+        }
+        moves.reverse();
+        Some(moves)
+    }
+
+    /// The board that `scramble`, a move sequence as `parse_moves` reads it,
+    /// makes out of this one.
+    pub fn scrambled(&self, scramble: &str) -> Result<Puzzle, ScrambleError> {
+        self.apply_moves(&parse_moves(scramble)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    #[test]
+    fn letters_round_trip() {
+        for direction in Direction::all() {
+            let letter = direction.to_string().chars().next().unwrap();
+            assert_eq!(Direction::from_char(letter), Some(direction));
+            assert_eq!(
+                Direction::from_char(letter.to_ascii_lowercase()),
+                Some(direction)
+            );
+        }
+        assert_eq!(Direction::from_char('X'), None);
+    }
+
+    #[test]
+    fn broken_paths_are_caught() {
+        let moves = parse_moves("U L D").unwrap();
+        let mut path = vec![goal()];
+        for &direction in &moves {
+            path.push(path.last().unwrap().move_zero(direction).unwrap());
+        }
+        assert_eq!(verify_path(&path), Ok(()));
+        assert_eq!(verify_path(&path[..1]), Ok(()));
+        assert_eq!(verify_path(&[]), Ok(()));
+
+        // A skipped board, a board repeated in place of a move, and a
+        // board swapped for one with two tiles exchanged.
+        let mut skipped = path.clone();
+        skipped.remove(2);
+        assert_eq!(verify_path(&skipped), Err(1));
+        let mut repeated = path.clone();
+        repeated.insert(1, goal());
+        assert_eq!(verify_path(&repeated), Err(0));
+        let mut swapped = path.clone();
+        swapped[3] = swapped[3].swap_cells((0, 0), (2, 2));
+        assert_eq!(verify_path(&swapped), Err(2));
+    }
+
+    #[test]
+    fn parses_with_or_without_separators() {
+        let expected = vec![
+            Direction::Up,
+            Direction::Left,
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+        ];
+        assert_eq!(parse_moves("U L L D R"), Ok(expected.clone()));
+        assert_eq!(parse_moves("ulldr"), Ok(expected.clone()));
+        assert_eq!(parse_moves("U,L, L,D R"), Ok(expected));
+        assert_eq!(parse_moves(""), Ok(vec![]));
+        assert_eq!(parse_moves("U X"), Err(ScrambleError::InvalidMove('X')));
+    }
+
+    #[test]
+    fn scramble_moves_the_blank() {
+        assert_eq!(goal().scrambled(""), Ok(goal()));
+        assert_eq!(
+            goal().scrambled("U L"),
+            Ok(Puzzle::new([[0, 1, 3], [8, 2, 4], [7, 6, 5]]))
+        );
+
+        let there_and_back = goal().scrambled("U L D R").unwrap();
+        assert_eq!(there_and_back.scrambled("L U R D"), Ok(goal()));
+    }
+
+    #[test]
+    fn moves_to_neighbours_and_back() {
+        assert_eq!(goal().moves_to(&goal()), Some(vec![]));
+        for direction in Direction::all() {
+            let next = goal().move_zero(direction).unwrap();
+            assert_eq!(goal().moves_to(&next), Some(vec![direction]));
+            assert_eq!(next.moves_to(&goal()), Some(vec![direction.reverse()]));
+        }
+    }
+
+    #[test]
+    fn moves_to_finds_a_shortest_sequence() {
+        let target = goal().scrambled("U L D R D L").unwrap();
+        let moves = goal().moves_to(&target).unwrap();
+        assert_eq!(moves.len(), 6);
+        assert_eq!(goal().apply_moves(&moves), Ok(target));
+
+        // There and back cancels out.
+        let detour = goal().scrambled("U L R D L").unwrap();
+        assert_eq!(goal().moves_to(&detour), Some(vec![Direction::Left]));
+
+        let other_parity = goal().swap_cells((0, 0), (0, 1));
+        assert_eq!(goal().moves_to(&other_parity), None);
+    }
+
+    #[test]
+    fn illegal_move_is_reported() {
+        assert_eq!(
+            goal().scrambled("U U"),
+            Err(ScrambleError::IllegalMove {
+                index: 1,
+                direction: Direction::Up,
+            })
+        );
+    }
+}
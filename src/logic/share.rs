@@ -0,0 +1,302 @@
+use std::fmt;
+
+use super::{Direction, Puzzle, PuzzleError, ScrambleError, Solution, Strategy};
+
+/// Bumped whenever the layout of share codes changes, older codes are
+/// rejected.
+const SHARE_VERSION: u8 = 1;
+/// Strategy byte of a solution found by a heuristic `Strategy` does not list.
+const NO_STRATEGY: u8 = u8::MAX;
+/// URL-safe base64, so codes also fit in links.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A problem and its solution as a share code carries them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedProblem {
+    pub initial: Puzzle,
+    pub goal: Puzzle,
+    /// `None` when the solution came from a heuristic `Strategy` does not
+    /// list.
+    pub strategy: Option<Strategy>,
+    /// Blank moves from `initial` to `goal`.
+    pub moves: Vec<Direction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShareError {
+    /// A character outside the base64 alphabet of share codes.
+    InvalidCharacter(char),
+    /// The code ends before everything it announces.
+    Truncated,
+    UnsupportedVersion(u8),
+    InvalidBoard(PuzzleError),
+    UnknownStrategy(u8),
+    InvalidMoves(ScrambleError),
+    /// The moves are legal but end somewhere else than the goal.
+    MissesGoal,
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShareError::InvalidCharacter(c) => write!(f, "invalid character \"{}\"", c),
+            ShareError::Truncated => write!(f, "the code is cut short"),
+            ShareError::UnsupportedVersion(version) => {
+                write!(f, "unsupported share code version {}", version)
+            }
+            ShareError::InvalidBoard(e) => write!(f, "invalid board: {}", e),
+            ShareError::UnknownStrategy(index) => write!(f, "unknown strategy {}", index),
+            ShareError::InvalidMoves(e) => write!(f, "invalid moves: {}", e),
+            ShareError::MissesGoal => write!(f, "the moves do not reach the goal"),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+impl From<&Solution> for SharedProblem {
+    fn from(solution: &Solution) -> Self {
+        SharedProblem {
+            initial: solution.path[0],
+            goal: *solution.path.last().unwrap(),
+            strategy: Strategy::all()
+                .into_iter()
+                .find(|strategy| strategy.name() == solution.strategy),
+            moves: solution.moves.clone(),
+        }
+    }
+}
+
+impl SharedProblem {
+    /// The share code: a version byte, both boards at four bits a cell, the
+    /// strategy, a two byte move count and the moves at two bits each, all
+    /// in base64. A 20 move solution takes 24 characters.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![SHARE_VERSION];
+        let cells = [self.initial, self.goal]
+            .into_iter()
+            .flat_map(|board| (0..9).map(move |index| board.cell(index)))
+            .collect::<Vec<_>>();
+        bytes.extend(cells.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+
+        bytes.push(match self.strategy {
+            Some(strategy) => Strategy::all()
+                .into_iter()
+                .position(|known| known == strategy)
+                .unwrap() as u8,
+            None => NO_STRATEGY,
+        });
+        bytes.extend((self.moves.len() as u16).to_be_bytes());
+        bytes.extend(self.moves.chunks(4).map(|moves| {
+            moves.iter().enumerate().fold(0, |byte, (k, &direction)| {
+                byte | direction_index(direction) << (6 - 2 * k)
+            })
+        }));
+
+        to_base64(&bytes)
+    }
+
+    /// Reads a code written by `encode`, surrounding whitespace ignored.
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let bytes = from_base64(code.trim())?;
+        let mut bytes = bytes.iter().copied();
+        let mut next = || bytes.next().ok_or(ShareError::Truncated);
+
+        let version = next()?;
+        if version != SHARE_VERSION {
+            return Err(ShareError::UnsupportedVersion(version));
+        }
+
+        let mut cells = Vec::with_capacity(18);
+        for _ in 0..9 {
+            let pair = next()?;
+            cells.extend([pair >> 4, pair & 0xf]);
+        }
+        let board = |cells: &[u8]| {
+            Puzzle::try_from(<[u8; 9]>::try_from(cells).unwrap()).map_err(ShareError::InvalidBoard)
+        };
+        let (initial, goal) = (board(&cells[..9])?, board(&cells[9..])?);
+
+        let strategy = match next()? {
+            NO_STRATEGY => None,
+            index => Some(
+                *Strategy::all()
+                    .get(index as usize)
+                    .ok_or(ShareError::UnknownStrategy(index))?,
+            ),
+        };
+
+        let len = u16::from_be_bytes([next()?, next()?]) as usize;
+        let mut moves = Vec::with_capacity(len);
+        while moves.len() < len {
+            let byte = next()?;
+            for k in 0..4.min(len - moves.len()) {
+                moves.push(Direction::all()[(byte >> (6 - 2 * k) & 0b11) as usize]);
+            }
+        }
+
+        let end = initial
+            .apply_moves(&moves)
+            .map_err(ShareError::InvalidMoves)?;
+        if end != goal {
+            return Err(ShareError::MissesGoal);
+        }
+
+        Ok(SharedProblem {
+            initial,
+            goal,
+            strategy,
+            moves,
+        })
+    }
+}
+
+/// The share code of `solution`, see `SharedProblem::encode`.
+pub fn encode_share(solution: &Solution) -> String {
+    SharedProblem::from(solution).encode()
+}
+
+/// The problem and solution in a share code, see `SharedProblem::decode`.
+pub fn decode_share(code: &str) -> Result<SharedProblem, ShareError> {
+    SharedProblem::decode(code)
+}
+
+fn direction_index(direction: Direction) -> u8 {
+    Direction::all()
+        .into_iter()
+        .position(|known| known == direction)
+        .unwrap() as u8
+}
+
+impl Puzzle {
+    /// The tile of the cell at row-major `index`.
+    fn cell(&self, index: usize) -> u8 {
+        let (i, j) = Puzzle::index_to_coord(index);
+        self.get_value(i, j)
+    }
+}
+
+/// Base64 without padding, the length tells how many bytes the last group
+/// holds.
+fn to_base64(bytes: &[u8]) -> String {
+    let mut code = String::new();
+    for group in bytes.chunks(3) {
+        let bits = group.iter().enumerate().fold(0u32, |bits, (k, &byte)| {
+            bits | (byte as u32) << (16 - 8 * k)
+        });
+        for k in 0..=group.len() {
+            code.push(ALPHABET[(bits >> (18 - 6 * k) & 0x3f) as usize] as char);
+        }
+    }
+    code
+}
+
+fn from_base64(code: &str) -> Result<Vec<u8>, ShareError> {
+    let values = code
+        .chars()
+        .map(|c| {
+            ALPHABET
+                .iter()
+                .position(|&letter| letter as char == c)
+                .map(|value| value as u32)
+                .ok_or(ShareError::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut bytes = Vec::new();
+    for group in values.chunks(4) {
+        if group.len() == 1 {
+            return Err(ShareError::Truncated);
+        }
+        let bits = group
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (k, &value)| bits | value << (18 - 6 * k));
+        for k in 0..group.len() - 1 {
+            bytes.push((bits >> (16 - 8 * k)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{parse_moves, solve, SolveConfig};
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    #[test]
+    fn base64_round_trips_every_length() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for len in 0..10 {
+            let code = to_base64(&bytes[250 - len..250]);
+            assert_eq!(from_base64(&code), Ok(bytes[250 - len..250].to_vec()));
+        }
+        assert_eq!(from_base64("AB=C"), Err(ShareError::InvalidCharacter('=')));
+    }
+
+    #[test]
+    fn solution_round_trips() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        for strategy in Strategy::all() {
+            let solution = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            let shared = decode_share(&encode_share(&solution)).unwrap();
+            assert_eq!(
+                shared,
+                SharedProblem {
+                    initial,
+                    goal: goal(),
+                    strategy: Some(strategy),
+                    moves: solution.moves.clone(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn codes_are_compact() {
+        // A loop around the top left corner, five times over.
+        let moves = parse_moves("ULDR").unwrap().repeat(5);
+        let undo = moves
+            .iter()
+            .rev()
+            .map(Direction::reverse)
+            .collect::<Vec<_>>();
+        let shared = SharedProblem {
+            initial: goal().apply_moves(&undo).unwrap(),
+            goal: goal(),
+            strategy: None,
+            moves,
+        };
+
+        let code = shared.encode();
+        assert_eq!(code.len(), 24);
+        assert_eq!(SharedProblem::decode(&format!(" {}\n", code)), Ok(shared));
+    }
+
+    #[test]
+    fn broken_codes_are_rejected() {
+        let solution = solve(
+            goal().scrambled("U L").unwrap(),
+            goal(),
+            &SolveConfig::default(),
+        )
+        .unwrap();
+        let code = encode_share(&solution);
+
+        assert_eq!(
+            decode_share(&code[..code.len() - 2]),
+            Err(ShareError::Truncated)
+        );
+        assert_eq!(decode_share("A!"), Err(ShareError::InvalidCharacter('!')));
+        let mut other_version = code.clone();
+        other_version.replace_range(..2, "Ag");
+        assert_eq!(
+            decode_share(&other_version),
+            Err(ShareError::UnsupportedVersion(2))
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    sync::Arc,
+};
+
+use super::{Direction, Puzzle};
+
+/// Every board reachable from a goal, found by a full BFS backwards from it.
+/// Each board stores the move that brings it one step closer to the goal and
+/// its distance from the goal.
+///
+/// The boards are shared rather than owned, so a clone costs a reference
+/// count instead of a copy of all 181,440 entries, and every consumer can
+/// hold its own. The count is an `Arc`, not an `Rc`, so clones can also be
+/// handed to a solve running on a background thread.
+#[derive(Clone)]
+pub struct SolutionMap {
+    goal: Puzzle,
+    map: Arc<HashMap<Puzzle, (Option<Direction>, u32)>>,
+}
+
+/// Builds a `SolutionMap` a few boards at a time, so a UI can spread the
+/// BFS over several frames and show how far it got.
+pub struct SolutionMapBuilder {
+    goal: Puzzle,
+    map: HashMap<Puzzle, (Option<Direction>, u32)>,
+    queue: VecDeque<Puzzle>,
+}
+
+impl SolutionMapBuilder {
+    pub fn new(goal: Puzzle) -> Self {
+        SolutionMapBuilder {
+            goal,
+            map: HashMap::from([(goal, (None, 0))]),
+            queue: VecDeque::from([goal]),
+        }
+    }
+
+    pub fn goal(&self) -> &Puzzle {
+        &self.goal
+    }
+
+    /// Expands up to `boards` more boards of the BFS.
+    pub fn step(&mut self, boards: usize) {
+        for _ in 0..boards {
+            let Some(current) = self.queue.pop_front() else {
+                break;
+            };
+            let depth = self.map[&current].1;
+
+            for direction in Direction::all() {
+                if let Some(next) = current.move_zero(direction) {
+                    if let Entry::Vacant(entry) = self.map.entry(next) {
+                        entry.insert((Some(direction.reverse()), depth + 1));
+                        self.queue.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Share of the reachable boards found so far, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        let cells = Puzzle::SIDE * Puzzle::SIDE;
+        // Half of all arrangements share the parity of the goal.
+        let reachable = (1..=cells).product::<usize>() / 2;
+        self.map.len() as f64 / reachable as f64
+    }
+
+    /// The finished map, `None` while boards are left to expand.
+    pub fn finish(self) -> Option<SolutionMap> {
+        self.is_complete().then(|| SolutionMap {
+            goal: self.goal,
+            map: Arc::new(self.map),
+        })
+    }
+}
+
+impl SolutionMap {
+    /// Builds the whole map at once, see `SolutionMapBuilder` to spread it
+    /// out.
+    pub fn new(goal: Puzzle) -> Self {
+        let mut builder = SolutionMapBuilder::new(goal);
+        while !builder.is_complete() {
+            builder.step(usize::MAX);
+        }
+        builder.finish().unwrap()
+    }
+
+    pub fn goal(&self) -> &Puzzle {
+        &self.goal
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Number of moves from `puzzle` to the goal, `None` if it is unreachable.
+    pub fn distance(&self, puzzle: &Puzzle) -> Option<u32> {
+        self.map.get(puzzle).map(|&(_, depth)| depth)
+    }
+
+    /// The blank move that takes `puzzle` one step closer to the goal, `None`
+    /// at the goal or if it is unreachable.
+    pub fn next_move(&self, puzzle: &Puzzle) -> Option<Direction> {
+        self.map.get(puzzle).and_then(|&(direction, _)| direction)
+    }
+
+    /// Every reachable board with its distance from the goal, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Puzzle, u32)> + '_ {
+        self.map.iter().map(|(puzzle, &(_, depth))| (puzzle, depth))
+    }
+
+    /// The optimal path from `start` to the goal, both ends included.
+    pub fn reconstruct_path(&self, start: Puzzle) -> Option<Vec<Puzzle>> {
+        self.map
+            .contains_key(&start)
+            .then(|| self.path_iter(start).collect())
+    }
+
+    /// Lazily walks the optimal path from `start` to the goal, both ends
+    /// included. Yields nothing when `start` cannot reach the goal.
+    pub fn path_iter(&self, start: Puzzle) -> impl Iterator<Item = Puzzle> + '_ {
+        let start = self.map.contains_key(&start).then_some(start);
+
+        std::iter::successors(start, |current| match self.map.get(current) {
+            Some((Some(direction), _)) => current.move_zero(*direction),
+            _ => None,
+        })
+    }
+
+    /// Number of reachable boards at each distance from the goal
+    /// (index = distance, value = count).
+    pub fn distance_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+
+        for &(_, depth) in self.map.values() {
+            let depth = depth as usize;
+            if histogram.len() <= depth {
+                histogram.resize(depth + 1, 0);
+            }
+            histogram[depth] += 1;
+        }
+
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::verify_path;
+
+    #[test]
+    fn built_in_steps_matches_built_at_once() {
+        let goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let mut builder = SolutionMapBuilder::new(goal);
+        let mut last_progress = builder.progress();
+
+        while !builder.is_complete() {
+            builder.step(10_000);
+            assert!(builder.progress() > last_progress);
+            last_progress = builder.progress();
+        }
+        assert_eq!(last_progress, 1.0);
+
+        let stepped = builder.finish().unwrap();
+        assert_eq!(stepped.map, SolutionMap::new(goal).map);
+        assert!(SolutionMapBuilder::new(goal).finish().is_none());
+    }
+
+    #[test]
+    fn histogram_covers_every_reachable_board() {
+        let goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let map = SolutionMap::new(goal);
+        let histogram = map.distance_histogram();
+
+        assert_eq!(histogram.iter().sum::<usize>(), map.len());
+        // Half of the 9! arrangements share the parity of the goal.
+        assert_eq!(map.len(), 181440);
+        // The goal itself, then one board per move of its central blank.
+        assert_eq!(histogram[..2], [1, 4]);
+    }
+
+    #[test]
+    fn path_iter_walks_a_shortest_path_to_the_goal() {
+        let goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let map = SolutionMap::new(goal);
+        let unreachable = goal.swap_cells((0, 0), (0, 1));
+
+        for start in [
+            goal,
+            goal.scrambled("U").unwrap(),
+            goal.scrambled("U L D R R D").unwrap(),
+            Puzzle::new([[5, 6, 7], [4, 0, 8], [3, 2, 1]]),
+        ] {
+            let walked = map.path_iter(start).collect::<Vec<_>>();
+            assert_eq!(walked.first(), Some(&start));
+            assert_eq!(walked.last(), Some(&goal));
+            assert_eq!(verify_path(&walked), Ok(()));
+            assert_eq!(Some(walked.len() as u32 - 1), map.distance(&start));
+            assert_eq!(map.reconstruct_path(start), Some(walked));
+        }
+        assert_eq!(map.path_iter(unreachable).next(), None);
+        assert_eq!(map.reconstruct_path(unreachable), None);
+    }
+
+    #[test]
+    fn clones_share_the_boards() {
+        let goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let map = SolutionMap::new(goal);
+        let clone = map.clone();
+
+        assert!(Arc::ptr_eq(&map.map, &clone.map));
+        assert_eq!(Arc::strong_count(&map.map), 2);
+        drop(clone);
+        assert_eq!(Arc::strong_count(&map.map), 1);
+
+        let start = goal.scrambled("U L D").unwrap();
+        assert_eq!(map.distance(&start), Some(3));
+        assert_eq!(map.next_move(&start), Some(Direction::Up));
+        assert_eq!(map.next_move(&goal), None);
+        assert_eq!(map.iter().count(), map.len());
+    }
+}
@@ -0,0 +1,735 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{
+    solve_from_initials_bounded, verify_path, AStarHeuristic1, AStarHeuristic2, BfsHeuristic,
+    CostWeights, Direction, Heuristic, ManhattanHeuristic, ManhattanOrMisplaced, Puzzle,
+    SearchProgress, SearchTree,
+};
+
+/// The heuristics the solver can run with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    Bfs,
+    AStar1,
+    AStar2,
+    Manhattan,
+    /// `ManhattanOrMisplaced`, the only combined heuristic.
+    Max,
+}
+
+impl Strategy {
+    pub const fn all() -> [Strategy; 5] {
+        [
+            Strategy::Bfs,
+            Strategy::AStar1,
+            Strategy::AStar2,
+            Strategy::Manhattan,
+            Strategy::Max,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Bfs => BfsHeuristic::new().name(),
+            Strategy::AStar1 => AStarHeuristic1::new().name(),
+            Strategy::AStar2 => AStarHeuristic2::new().name(),
+            Strategy::Manhattan => ManhattanHeuristic::new().name(),
+            Strategy::Max => ManhattanOrMisplaced::new().name(),
+        }
+    }
+
+    pub fn is_admissible(&self) -> bool {
+        match self {
+            Strategy::Bfs => BfsHeuristic::new().is_admissible(),
+            Strategy::AStar1 => AStarHeuristic1::new().is_admissible(),
+            Strategy::AStar2 => AStarHeuristic2::new().is_admissible(),
+            Strategy::Manhattan => ManhattanHeuristic::new().is_admissible(),
+            Strategy::Max => ManhattanOrMisplaced::new().is_admissible(),
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        match self {
+            Strategy::Bfs => BfsHeuristic::new().is_consistent(),
+            Strategy::AStar1 => AStarHeuristic1::new().is_consistent(),
+            Strategy::AStar2 => AStarHeuristic2::new().is_consistent(),
+            Strategy::Manhattan => ManhattanHeuristic::new().is_consistent(),
+            Strategy::Max => ManhattanOrMisplaced::new().is_consistent(),
+        }
+    }
+
+    /// The estimate of a fresh heuristic, one that has not learned anything
+    /// from a search.
+    pub fn estimate_h(&self, current: &Puzzle, goal: &Puzzle) -> i32 {
+        match self {
+            Strategy::Bfs => BfsHeuristic::new().estimate_h(current, goal),
+            Strategy::AStar1 => AStarHeuristic1::new().estimate_h(current, goal),
+            Strategy::AStar2 => AStarHeuristic2::new().estimate_h(current, goal),
+            Strategy::Manhattan => ManhattanHeuristic::new().estimate_h(current, goal),
+            Strategy::Max => ManhattanOrMisplaced::new().estimate_h(current, goal),
+        }
+    }
+
+    /// Names of the parts of a combined heuristic, `None` for the others.
+    pub fn sources(&self) -> Option<[&'static str; 2]> {
+        match self {
+            Strategy::Max => Some(ManhattanOrMisplaced::new().source_names()),
+            _ => None,
+        }
+    }
+
+    /// Which part of a combined heuristic gives the fresh estimate of
+    /// `current`, an index into `sources`. `None` for the others.
+    pub fn estimate_source(&self, current: &Puzzle, goal: &Puzzle) -> Option<usize> {
+        match self {
+            Strategy::Max => Some(
+                ManhattanOrMisplaced::new()
+                    .estimate_with_source(current, goal)
+                    .1,
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Everything `solve` can be asked to search with. The default is an A*
+/// search with the Manhattan heuristic, generating neighbours in
+/// `Direction::all()` order, ranking them by plain g + h and without any
+/// limit. The builder methods change one setting each, as in
+/// `SolveConfig::new(Strategy::Bfs).max_expanded(10_000)`.
+#[derive(Clone, Debug)]
+pub struct SolveConfig {
+    pub strategy: Strategy,
+    /// Order the blank moves of every board are tried in, which breaks
+    /// ties between equally good boards.
+    pub order: [Direction; 4],
+    pub weights: CostWeights,
+    /// See `solve_from_initials_bounded` for what is lost once the open set
+    /// holds this many boards.
+    pub max_open: Option<usize>,
+    /// Boards closed before the search gives up.
+    pub max_expanded: Option<usize>,
+    /// Time the search may run before it gives up.
+    pub time_limit: Option<Duration>,
+    /// The search gives up once this is set, from whichever thread.
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl SolveConfig {
+    /// The default configuration searching with `strategy`.
+    pub fn new(strategy: Strategy) -> Self {
+        SolveConfig {
+            strategy,
+            ..SolveConfig::default()
+        }
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn order(mut self, order: [Direction; 4]) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn weights(mut self, weights: CostWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn max_open(mut self, max_open: usize) -> Self {
+        self.max_open = Some(max_open);
+        self
+    }
+
+    pub fn max_expanded(mut self, max_expanded: usize) -> Self {
+        self.max_expanded = Some(max_expanded);
+        self
+    }
+
+    pub fn time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    pub fn cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Whether a search as this configuration says finds a shortest path.
+    pub fn proves_optimal(&self) -> bool {
+        // The search never reopens closed boards, so a shortest path needs a
+        // consistent heuristic, weights that keep it so and no forgotten boards.
+        self.strategy.is_admissible()
+            && self.strategy.is_consistent()
+            && self.weights.keeps_optimality()
+            && self.max_open.is_none()
+    }
+}
+
+impl Default for SolveConfig {
+    fn default() -> Self {
+        SolveConfig {
+            strategy: Strategy::Manhattan,
+            order: Direction::all(),
+            weights: CostWeights::default(),
+            max_open: None,
+            max_expanded: None,
+            time_limit: None,
+            cancel: None,
+        }
+    }
+}
+
+/// Counters of the search that found a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Boards closed by the search before it reached the goal.
+    pub expanded: usize,
+    /// Boards pushed to the open set, initial ones included.
+    pub generated: usize,
+    /// Most boards the open set held at once.
+    pub max_open: usize,
+    pub elapsed: Duration,
+}
+
+/// Everything known about one solve, for whatever displays or exports it.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// From the initial board to the goal, both included.
+    pub path: Vec<Puzzle>,
+    /// The blank moves along `path`, one fewer than its boards.
+    pub moves: Vec<Direction>,
+    pub stats: SearchStats,
+    /// Name of the heuristic the search ran with.
+    pub strategy: String,
+    /// Whether `path` is guaranteed to be a shortest one.
+    pub optimal: bool,
+}
+
+struct ClosedSet<'a> {
+    goal: Puzzle,
+    map: HashMap<Puzzle, (Puzzle, i32)>,
+    progress: SearchProgress,
+    config: &'a SolveConfig,
+    start: Instant,
+}
+
+impl<'a> ClosedSet<'a> {
+    fn new(goal: Puzzle, config: &'a SolveConfig) -> Self {
+        ClosedSet {
+            goal,
+            map: HashMap::new(),
+            progress: SearchProgress::default(),
+            config,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl SearchTree for ClosedSet<'_> {
+    fn goal_reached(&self) -> bool {
+        self.map.contains_key(&self.goal)
+    }
+    fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+        self.map.get(key).copied()
+    }
+    fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+        self.map.insert(key, value);
+    }
+    fn on_progress(&mut self, progress: &SearchProgress) {
+        self.progress = *progress;
+    }
+    fn is_cancelled(&self) -> bool {
+        limit_hit(self.config, &self.progress, self.start)
+    }
+}
+
+/// Whether a search that started at `start` and got as far as `progress`
+/// ran into one of the limits of `config`, or was cancelled.
+fn limit_hit(config: &SolveConfig, progress: &SearchProgress, start: Instant) -> bool {
+    let expanded_all = config
+        .max_expanded
+        .is_some_and(|max| progress.expanded >= max);
+    let out_of_time = config
+        .time_limit
+        .is_some_and(|limit| start.elapsed() >= limit);
+    let cancelled = config
+        .cancel
+        .as_ref()
+        .is_some_and(|cancel| cancel.load(Ordering::Relaxed));
+    expanded_all || out_of_time || cancelled
+}
+
+/// Closed set of `solve_count`, which keeps the g of every closed board and
+/// not its parent. Without parents the search cannot walk the path back and
+/// returns nothing, the g of the goal once it is closed is the answer.
+struct CountingClosedSet<'a> {
+    goal: Puzzle,
+    g: HashMap<Puzzle, i32>,
+    goal_g: Option<i32>,
+    progress: SearchProgress,
+    config: &'a SolveConfig,
+    start: Instant,
+}
+
+impl SearchTree for CountingClosedSet<'_> {
+    fn goal_reached(&self) -> bool {
+        self.goal_g.is_some()
+    }
+    fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+        self.g.get(key).map(|&g| (*key, g))
+    }
+    fn set(&mut self, key: Puzzle, (_, g): (Puzzle, i32)) {
+        if key == self.goal {
+            self.goal_g = Some(g);
+        }
+        self.g.insert(key, g);
+    }
+    fn on_progress(&mut self, progress: &SearchProgress) {
+        self.progress = *progress;
+    }
+    fn is_cancelled(&self) -> bool {
+        limit_hit(self.config, &self.progress, self.start)
+    }
+}
+
+/// What `solve_count` finds out: how long the solution is, not what it is.
+#[derive(Clone, Debug)]
+pub struct StepCount {
+    pub steps: usize,
+    pub stats: SearchStats,
+    /// Name of the heuristic the search ran with.
+    pub strategy: String,
+    /// Whether `steps` is guaranteed to be the fewest possible.
+    pub optimal: bool,
+}
+
+fn solve_with<H: Heuristic>(
+    initial: Puzzle,
+    goal: Puzzle,
+    config: &SolveConfig,
+) -> Option<Solution> {
+    let mut closed_set = ClosedSet::new(goal, config);
+    let (_, path) = solve_from_initials_bounded::<_, H>(
+        &[initial],
+        goal,
+        &mut closed_set,
+        config.order,
+        config.weights,
+        config.max_open,
+    )?;
+    debug_assert_eq!(verify_path(&path), Ok(()), "illegal move in {:?}", path);
+
+    Some(Solution {
+        moves: path
+            .windows(2)
+            .filter_map(|pair| pair[0].direction_to(&pair[1]))
+            .collect(),
+        path,
+        stats: SearchStats {
+            expanded: closed_set.map.len(),
+            generated: closed_set.progress.generated,
+            max_open: closed_set.progress.max_open,
+            elapsed: closed_set.start.elapsed(),
+        },
+        strategy: H::new().name().to_string(),
+        optimal: config.proves_optimal(),
+    })
+}
+
+/// Searches for a path from `initial` to `goal` as `config` says, `None`
+/// when the goal cannot be reached or the search hits one of the limits of
+/// `config` first.
+pub fn solve(initial: Puzzle, goal: Puzzle, config: &SolveConfig) -> Option<Solution> {
+    match config.strategy {
+        Strategy::Bfs => solve_with::<BfsHeuristic>(initial, goal, config),
+        Strategy::AStar1 => solve_with::<AStarHeuristic1>(initial, goal, config),
+        Strategy::AStar2 => solve_with::<AStarHeuristic2>(initial, goal, config),
+        Strategy::Manhattan => solve_with::<ManhattanHeuristic>(initial, goal, config),
+        Strategy::Max => solve_with::<ManhattanOrMisplaced>(initial, goal, config),
+    }
+}
+
+fn count_with<H: Heuristic>(
+    initial: Puzzle,
+    goal: Puzzle,
+    config: &SolveConfig,
+) -> Option<StepCount> {
+    let mut closed_set = CountingClosedSet {
+        goal,
+        g: HashMap::new(),
+        goal_g: None,
+        progress: SearchProgress::default(),
+        config,
+        start: Instant::now(),
+    };
+    solve_from_initials_bounded::<_, H>(
+        &[initial],
+        goal,
+        &mut closed_set,
+        config.order,
+        config.weights,
+        config.max_open,
+    );
+
+    Some(StepCount {
+        steps: closed_set.goal_g? as usize,
+        stats: SearchStats {
+            expanded: closed_set.g.len(),
+            generated: closed_set.progress.generated,
+            max_open: closed_set.progress.max_open,
+            elapsed: closed_set.start.elapsed(),
+        },
+        strategy: H::new().name().to_string(),
+        optimal: config.proves_optimal(),
+    })
+}
+
+/// Same search as `solve`, but only counting the moves of the solution.
+/// Closed boards are kept without their parents and no path is walked back,
+/// which is faster and takes less memory when only the difficulty of many
+/// boards matters.
+pub fn solve_count(initial: Puzzle, goal: Puzzle, config: &SolveConfig) -> Option<StepCount> {
+    match config.strategy {
+        Strategy::Bfs => count_with::<BfsHeuristic>(initial, goal, config),
+        Strategy::AStar1 => count_with::<AStarHeuristic1>(initial, goal, config),
+        Strategy::AStar2 => count_with::<AStarHeuristic2>(initial, goal, config),
+        Strategy::Manhattan => count_with::<ManhattanHeuristic>(initial, goal, config),
+        Strategy::Max => count_with::<ManhattanOrMisplaced>(initial, goal, config),
+    }
+}
+
+/// Same as `solve` with `strategy` and all else default, trying the blank
+/// moves of every board in `order`.
+pub fn solve_in_order(
+    initial: Puzzle,
+    goal: Puzzle,
+    strategy: Strategy,
+    order: [Direction; 4],
+) -> Option<Solution> {
+    solve(initial, goal, &SolveConfig::new(strategy).order(order))
+}
+
+/// Same as `solve_in_order`, ranking open boards by `weights` instead of
+/// g + h.
+pub fn solve_weighted(
+    initial: Puzzle,
+    goal: Puzzle,
+    strategy: Strategy,
+    order: [Direction; 4],
+    weights: CostWeights,
+) -> Option<Solution> {
+    let config = SolveConfig::new(strategy).order(order).weights(weights);
+    solve(initial, goal, &config)
+}
+
+/// Same as `solve` with `strategy` and all else default, keeping at most
+/// `max_open` boards in the open set. See `solve_from_initials_bounded` for
+/// what is lost once the bound is hit.
+pub fn solve_bounded(
+    initial: Puzzle,
+    goal: Puzzle,
+    strategy: Strategy,
+    max_open: usize,
+) -> Option<Solution> {
+    solve(
+        initial,
+        goal,
+        &SolveConfig::new(strategy).max_open(max_open),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{solve_from_initials, solve_from_initials_weighted};
+
+    fn goal() -> Puzzle {
+        Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]])
+    }
+
+    fn assert_valid_path(solution: &Solution, initial: Puzzle, goal: Puzzle) {
+        assert_eq!(solution.path.first(), Some(&initial));
+        assert_eq!(solution.path.last(), Some(&goal));
+        for pair in solution.path.windows(2) {
+            assert!(pair[0].direction_to(&pair[1]).is_some());
+        }
+    }
+
+    #[test]
+    fn solves_solvable_board() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in Strategy::all() {
+            let solution = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            assert_valid_path(&solution, initial, goal());
+            assert!(solution.stats.expanded >= solution.path.len());
+            // Every closed board was pushed first, and so was what is left
+            // open.
+            assert!(solution.stats.generated >= solution.stats.expanded);
+            assert!(solution.stats.generated >= solution.stats.max_open);
+        }
+    }
+
+    #[test]
+    fn solution_describes_its_search() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in Strategy::all() {
+            let solution = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            assert_eq!(solution.moves.len(), solution.path.len() - 1);
+            let mut board = initial;
+            for &direction in &solution.moves {
+                board = board.move_zero(direction).unwrap();
+            }
+            assert_eq!(board, goal());
+            assert_eq!(solution.strategy, strategy.name());
+        }
+
+        assert!(
+            solve(initial, goal(), &SolveConfig::new(Strategy::Manhattan))
+                .unwrap()
+                .optimal
+        );
+        assert!(
+            !solve(initial, goal(), &SolveConfig::new(Strategy::AStar1))
+                .unwrap()
+                .optimal
+        );
+        assert!(
+            !solve_bounded(initial, goal(), Strategy::Manhattan, 1000)
+                .unwrap()
+                .optimal
+        );
+    }
+
+    #[test]
+    fn optimal_strategies_find_shortest_path() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in [Strategy::Bfs, Strategy::AStar2, Strategy::Manhattan] {
+            let solution = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            assert_eq!(solution.path.len() - 1, 5, "{}", strategy.name());
+        }
+    }
+
+    #[test]
+    fn count_matches_the_full_solve() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in Strategy::all() {
+            let config = SolveConfig::new(strategy);
+            let solution = solve(initial, goal(), &config).unwrap();
+            let count = solve_count(initial, goal(), &config).unwrap();
+            assert_eq!(count.steps, solution.moves.len());
+            assert_eq!(count.stats.expanded, solution.stats.expanded);
+            assert_eq!(count.stats.generated, solution.stats.generated);
+            assert_eq!(count.optimal, solution.optimal);
+        }
+
+        assert_eq!(
+            solve_count(goal(), goal(), &SolveConfig::default()).map(|count| count.steps),
+            Some(0)
+        );
+        let unreachable = goal().swap_cells((0, 0), (0, 1));
+        assert!(solve_count(unreachable, goal(), &SolveConfig::default()).is_none());
+    }
+
+    #[test]
+    fn already_solved_board() {
+        for strategy in Strategy::all() {
+            let solution = solve(goal(), goal(), &SolveConfig::new(strategy)).unwrap();
+            assert_eq!(solution.path, vec![goal()]);
+            assert_eq!(solution.stats.expanded, 1);
+            assert_eq!(solution.stats.generated, 1);
+            assert_eq!(solution.stats.max_open, 1);
+        }
+    }
+
+    #[test]
+    fn expansion_order_changes_tree_but_not_path_length() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let explore = |order| {
+            let config = SolveConfig::default();
+            let mut closed_set = ClosedSet::new(goal(), &config);
+            let (_, path) = solve_from_initials_weighted::<_, BfsHeuristic>(
+                &[initial],
+                goal(),
+                &mut closed_set,
+                order,
+                CostWeights::default(),
+            )
+            .unwrap();
+            (closed_set.map, path.len())
+        };
+
+        let (default_tree, default_len) = explore(Direction::all());
+        let (reversed_tree, reversed_len) = explore([
+            Direction::Right,
+            Direction::Left,
+            Direction::Down,
+            Direction::Up,
+        ]);
+
+        assert_ne!(default_tree, reversed_tree);
+        assert_eq!(default_len, reversed_len);
+    }
+
+    #[test]
+    fn default_weights_are_plain_a_star() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in Strategy::all() {
+            let plain = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            let weighted = solve_weighted(
+                initial,
+                goal(),
+                strategy,
+                Direction::all(),
+                CostWeights::new(1.0, 1.0),
+            )
+            .unwrap();
+            assert_eq!(plain.path, weighted.path);
+            assert_eq!(plain.stats.expanded, weighted.stats.expanded);
+        }
+    }
+
+    #[test]
+    fn zero_h_weight_is_bfs() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let bfs = solve(initial, goal(), &SolveConfig::new(Strategy::Bfs)).unwrap();
+
+        for strategy in Strategy::all() {
+            let dijkstra = solve_weighted(
+                initial,
+                goal(),
+                strategy,
+                Direction::all(),
+                CostWeights::new(1.0, 0.0),
+            )
+            .unwrap();
+            assert_eq!(dijkstra.path, bfs.path, "{}", strategy.name());
+            assert_eq!(
+                dijkstra.stats.expanded,
+                bfs.stats.expanded,
+                "{}",
+                strategy.name()
+            );
+        }
+    }
+
+    #[test]
+    fn zero_g_weight_is_greedy() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let greedy = solve_weighted(
+            initial,
+            goal(),
+            Strategy::Manhattan,
+            Direction::all(),
+            CostWeights::new(0.0, 1.0),
+        )
+        .unwrap();
+
+        assert_valid_path(&greedy, initial, goal());
+        // On this board the greedy search never has to back off a branch.
+        assert_eq!(greedy.stats.expanded, greedy.path.len());
+    }
+
+    #[test]
+    fn generous_open_set_bound_keeps_optimality() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in [Strategy::Bfs, Strategy::AStar2, Strategy::Manhattan] {
+            let unbounded = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            let bounded = solve_bounded(initial, goal(), strategy, 1000).unwrap();
+            assert_eq!(bounded.path.len() - 1, 5, "{}", strategy.name());
+            assert_eq!(
+                bounded.stats.expanded,
+                unbounded.stats.expanded,
+                "{}",
+                strategy.name()
+            );
+        }
+
+        // A tight bound still leaves Manhattan enough room on this board.
+        let tight = solve_bounded(initial, goal(), Strategy::Manhattan, 8).unwrap();
+        assert_eq!(tight.path.len() - 1, 5);
+    }
+
+    /// Path and expanded count of the search `solve` started from before it
+    /// took a configuration.
+    fn plain_search<H: Heuristic>(initial: Puzzle) -> (Vec<Puzzle>, usize) {
+        let config = SolveConfig::default();
+        let mut closed_set = ClosedSet::new(goal(), &config);
+        let (_, path) = solve_from_initials::<_, H>(&[initial], goal(), &mut closed_set).unwrap();
+        (path, closed_set.map.len())
+    }
+
+    #[test]
+    fn default_config_is_plain_search() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+
+        for strategy in Strategy::all() {
+            let expected = match strategy {
+                Strategy::Bfs => plain_search::<BfsHeuristic>(initial),
+                Strategy::AStar1 => plain_search::<AStarHeuristic1>(initial),
+                Strategy::AStar2 => plain_search::<AStarHeuristic2>(initial),
+                Strategy::Manhattan => plain_search::<ManhattanHeuristic>(initial),
+                Strategy::Max => plain_search::<ManhattanOrMisplaced>(initial),
+            };
+            let solution = solve(initial, goal(), &SolveConfig::new(strategy)).unwrap();
+            assert_eq!(
+                (solution.path, solution.stats.expanded),
+                expected,
+                "{}",
+                strategy.name()
+            );
+        }
+
+        let default = solve(initial, goal(), &SolveConfig::default()).unwrap();
+        assert_eq!(default.strategy, Strategy::Manhattan.name());
+        assert!(default.optimal);
+    }
+
+    #[test]
+    fn limits_stop_the_search() {
+        let initial = Puzzle::new([[2, 8, 3], [1, 6, 4], [7, 0, 5]]);
+        let config = SolveConfig::new(Strategy::Bfs);
+        let expanded = solve(initial, goal(), &config).unwrap().stats.expanded;
+
+        let enough = config.clone().max_expanded(expanded);
+        assert!(solve(initial, goal(), &enough).is_some());
+        let too_few = config.clone().max_expanded(expanded - 1);
+        assert!(solve(initial, goal(), &too_few).is_none());
+
+        let no_time = config.clone().time_limit(Duration::ZERO);
+        assert!(solve(initial, goal(), &no_time).is_none());
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let watched = config.cancel(cancel.clone());
+        assert!(solve(initial, goal(), &watched).is_some());
+        cancel.store(true, Ordering::Relaxed);
+        assert!(solve(initial, goal(), &watched).is_none());
+    }
+
+    #[test]
+    fn unsolvable_board() {
+        // Swapping two tiles flips the permutation parity.
+        let initial = Puzzle::new([[2, 1, 3], [8, 0, 4], [7, 6, 5]]);
+
+        for strategy in Strategy::all() {
+            assert!(solve(initial, goal(), &SolveConfig::new(strategy)).is_none());
+        }
+    }
+}
@@ -1,29 +1,60 @@
 #![windows_subsystem = "windows"]
 
 mod draw_tree;
-mod logic;
 pub mod name;
+mod tikz;
 mod ui;
 
 use std::{
     cell::Cell,
-    collections::{hash_map, HashMap},
-    iter::{Chain, Map},
+    collections::HashMap,
+    ffi::CString,
+    fs::File,
+    io::{self, BufWriter, Write},
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    time::Instant,
 };
 
-use draw_tree::{ElementPainter, IntRectBound, IterableSearchTree, PuzzleSizer, RcRefDrawTreeNode};
-use logic::{
-    solve_from_initial, AStarHeuristic1, AStarHeuristic2, BfsHeuristic, Heuristic, OpenSet, Puzzle,
-    SearchTree,
+use draw_tree::{
+    ElementPainter, FValues, HeuristicSources, IncrementalDrawTree, IntRectBound, PuzzleSizer,
+    RcRefDrawTreeNode, TreeLayout, TreeRoot, TreeStep,
 };
 use name::AUTHOR_NOTE;
+use nine::{
+    bench,
+    logic::{
+        find_parent_cycle, gods_number, solve_from_initials_bounded,
+        solve_from_initials_exhaustive, verify_path, AStarHeuristic1, AStarHeuristic2,
+        BfsHeuristic, CostWeights, Direction, GoalPreset, Heuristic, ManhattanHeuristic,
+        ManhattanOrMisplaced, OpenSet, Puzzle, RecordExpansions, SearchProgress, SearchStats,
+        SearchTree, SharedProblem, SolveConfig, Strategy,
+    },
+    search_dump::{SearchDump, SEARCH_DUMP_PATH},
+    session::{Session, SESSION_PATH},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use raylib::{prelude::*, rgui::RaylibDrawGui, rstr};
+use tikz::{TIKZ_PATH_FILE, TIKZ_TREE_FILE};
 use ui::{
-    elements::draw_puzzle,
+    auto_play::AutoPlay,
+    demo::{Demo, DEFAULT_IDLE_TIMEOUT},
+    elements::{
+        draw_badge, draw_blank_trace, draw_f_legend, draw_fringe_panel, draw_g_legend,
+        draw_last_move, draw_node_info, draw_pinned_goal, draw_puzzle, draw_puzzle_diff,
+        draw_source_legend, draw_tile_distances, BoardSizes,
+    },
     gif::{load_alice, ALICE_HEIGHT, ALICE_WIDTH},
     interactive_input::SetPuzzle,
+    library_panel::LibraryPanel,
+    move_list::MoveList,
+    parity_explorer::ParityExplorer,
+    play_mode::{Oracle, PlayMode},
+    replay::Replay,
+    report::Report,
+    share_panel::SharePanel,
+    theme::Theme,
+    tile_move::TileFlash,
 };
 
 pub trait AsMapSearchTree {
@@ -32,6 +63,12 @@ pub trait AsMapSearchTree {
     fn map(&self) -> &HashMap<Puzzle, (Puzzle, i32)>;
     fn map_mut(&mut self) -> &mut HashMap<Puzzle, (Puzzle, i32)>;
     fn step_callback(&mut self, _: &Puzzle, _: (&Puzzle, bool), _: &OpenSet);
+    /// Called when `key` is closed with `value`, after it is stored.
+    fn on_close(&mut self, _key: &Puzzle, _value: (Puzzle, i32)) {}
+    fn on_progress(&mut self, _progress: &SearchProgress) {}
+    fn is_cancelled(&self) -> bool {
+        false
+    }
 
     fn as_map_search_tree(&mut self) -> MapSearchTree<'_, Self>
     where
@@ -77,33 +114,101 @@ impl<T: AsMapSearchTree> DerefMut for MapSearchTree<'_, T> {
     }
 }
 
-fn print_map_search_tree<T: AsMapSearchTree>(m: &MapSearchTree<T>) {
-    if m.goal_reached() {
-        let mut vec = Vec::new();
-        let mut current = m.goal().clone();
+impl<T: AsMapSearchTree> MapSearchTree<'_, T> {
+    /// Consecutive boards of the solution path, one move apart, in order.
+    /// Yields nothing when there is no solution path.
+    fn iter_path_pairs(&self) -> impl Iterator<Item = (Puzzle, Puzzle)> {
+        let path = solution_path(self).unwrap_or_default();
+        (1..path.len()).map(move |i| (path[i - 1], path[i]))
+    }
+}
 
-        while &current != m.initial() {
-            if let Some((next, _)) = m.get(&current) {
-                vec.push(current);
-                current = next;
-            }
+fn solution_path<T: AsMapSearchTree>(m: &MapSearchTree<T>) -> Option<Vec<Puzzle>> {
+    if !m.goal_reached() {
+        return None;
+    }
+
+    let mut vec = Vec::new();
+    let mut current = *m.goal();
+
+    while &current != m.initial() {
+        // A parent loop never gets back to the initial board.
+        if vec.contains(&current) {
+            return None;
         }
+        let (next, _) = m.get(&current)?;
+        vec.push(current);
+        current = next;
+    }
+
+    vec.push(*m.initial());
+    vec.reverse();
+
+    Some(vec)
+}
 
-        vec.push(m.initial().clone());
-        vec.reverse();
+/// Reports a loop in the parent links of `m`, which would otherwise leave
+/// every walk up the tree spinning. Returns whether the map is sound.
+fn check_parent_links<T: AsMapSearchTree>(m: &MapSearchTree<T>) -> bool {
+    write_parent_links(m, &mut io::stdout().lock()).unwrap_or(false)
+}
 
-        for step in vec {
-            println!("{}", step);
+/// `check_parent_links` writing the loop, if any, to `out`.
+fn write_parent_links<T: AsMapSearchTree>(
+    m: &MapSearchTree<T>,
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    match find_parent_cycle(m.map()) {
+        Some(cycle) => {
+            writeln!(out, "Parent links loop through {} boards:", cycle.len())?;
+            for board in cycle {
+                writeln!(out, "{}", board)?;
+            }
+            Ok(false)
         }
+        None => Ok(true),
+    }
+}
+
+fn print_map_search_tree<T: AsMapSearchTree>(m: &MapSearchTree<T>) {
+    if let Err(e) = write_map_search_tree(m, &mut io::stdout().lock()) {
+        eprintln!("Cannot print the search tree: {}", e);
+    }
+}
+
+/// Writes the solution path of `m` board by board, its moves and the size
+/// of the tree to `out`, and returns the path. Nothing but the loop is
+/// written when the parent links have one.
+fn write_map_search_tree<T: AsMapSearchTree>(
+    m: &MapSearchTree<T>,
+    out: &mut dyn Write,
+) -> io::Result<Option<Vec<Puzzle>>> {
+    if !write_parent_links(m, out)? {
+        return Ok(None);
+    }
+
+    let path = solution_path(m);
+    for step in path.iter().flatten() {
+        writeln!(out, "{}", step)?;
     }
 
-    println!("Total nodes: {}", m.map().len());
+    let moves = m
+        .iter_path_pairs()
+        .filter_map(|(from, to)| from.direction_to(&to))
+        .map(|direction| direction.to_string())
+        .collect::<Vec<_>>();
+    if !moves.is_empty() {
+        writeln!(out, "Moves: {}", moves.join(" "))?;
+    }
+    writeln!(out, "Total nodes: {}", m.map().len())?;
+    Ok(path)
 }
 
 struct NativeSearchTree {
     goal: Puzzle,
     initial: Puzzle,
     map: HashMap<Puzzle, (Puzzle, i32)>,
+    expansion_order: Vec<Puzzle>,
 }
 
 impl AsMapSearchTree for NativeSearchTree {
@@ -122,6 +227,95 @@ impl AsMapSearchTree for NativeSearchTree {
     fn step_callback(&mut self, _: &Puzzle, _: (&Puzzle, bool), _: &OpenSet) {}
 }
 
+/// A finished solve put aside when another strategy solved the same boards,
+/// to flip back to without searching again.
+struct StrategyRun {
+    tree: NativeSearchTree,
+    stats: Option<SearchStats>,
+    failure: Option<String>,
+    weights: CostWeights,
+}
+
+impl StrategyRun {
+    fn searched(&self, initial: Puzzle, goal: Puzzle) -> bool {
+        self.tree.initial == initial && self.tree.goal == goal
+    }
+}
+
+/// Puts `run` of `strategy` on the stack, dropping the runs on other boards.
+fn stack_run(runs: &mut HashMap<Strategy, StrategyRun>, strategy: Strategy, run: StrategyRun) {
+    let (initial, goal) = (run.tree.initial, run.tree.goal);
+    runs.retain(|_, other| other.searched(initial, goal));
+    runs.insert(strategy, run);
+}
+
+#[derive(Clone, Copy)]
+struct AnimationSettings {
+    max_nodes: usize,
+    /// Search steps processed between two redraws. Every redraw is one frame
+    /// paced by the target FPS, so the search advances by
+    /// `fps * steps_per_frame` steps per second while animating.
+    steps_per_frame: usize,
+    show_fringe: bool,
+    /// Fade the boards of earlier frames, so the boards each frame adds
+    /// stand out.
+    wavefront: bool,
+    /// How the animated tree places the boards the search adds.
+    layout: TreeLayout,
+    /// Fail boards of the wrong parity right away instead of searching
+    /// them exhaustively.
+    check_parity: bool,
+    /// Keep the explored tree when the goal is never reached.
+    keep_unsolved: bool,
+    /// Order the blank moves of every board are tried in.
+    move_order: [Direction; 4],
+    /// Coefficients of g and h in the f open boards are ranked by.
+    weights: CostWeights,
+    theme: Theme,
+    /// Seconds the whole animated search should take, whatever its size.
+    /// Instead of every `steps_per_frame` steps, frames are drawn as the
+    /// schedule allows, see `Pacing`.
+    duration: Option<f64>,
+}
+
+/// Spreads the expansions of a search evenly over `duration` seconds. The
+/// pace needs the total number of expansions up front, so the search is run
+/// once without animation to count them, or the count is estimated when the
+/// goal cannot be reached. A search behind its schedule skips frames to
+/// catch up, which fast-forwards dense stretches; ahead of it every step
+/// gets a frame and the frame rate holds it back.
+#[derive(Clone, Copy, Debug)]
+struct Pacing {
+    total: usize,
+    duration: f64,
+    start: f64,
+}
+
+impl Pacing {
+    /// Expansions the search should have made by `now`.
+    fn due(&self, now: f64) -> usize {
+        let share = ((now - self.start) / self.duration).clamp(0.0, 1.0);
+        (self.total as f64 * share) as usize
+    }
+}
+
+/// What the boards of the drawn tree are colored by.
+#[derive(Clone, Copy, PartialEq)]
+enum BoardColors {
+    Plain,
+    F,
+    /// The part of a combined heuristic that gives the estimate, offered
+    /// only while the tree on screen was searched with one.
+    Source,
+}
+
+/// Which end of the solution path the view scrolls to after a solve.
+#[derive(Clone, Copy, PartialEq)]
+enum CenterOn {
+    Goal,
+    Initial,
+}
+
 struct AnimatedSearchTree<'handle> {
     goal: Puzzle,
     initial: Puzzle,
@@ -129,106 +323,87 @@ struct AnimatedSearchTree<'handle> {
     handle: &'handle mut RaylibHandle,
     thread: &'handle RaylibThread,
     max_nodes: Cell<usize>,
+    /// Set once the search is stopped from the animation.
+    cancelled: Cell<bool>,
+    steps_per_frame: usize,
+    pending_steps: usize,
+    pacing: Option<Pacing>,
+    /// Whether the view keeps the board being expanded near its center,
+    /// shared with the main screen so the choice holds for the next search.
+    follow: &'handle Cell<bool>,
+    /// Where the root of the animated tree is drawn. Follow mode eases it
+    /// towards the board being expanded, otherwise it is dragged by hand.
+    view_offset: (i32, i32),
+    /// Mouse position of the last frame while the view is being dragged.
+    drag_from: Option<(i32, i32)>,
+    show_fringe: bool,
+    wavefront: bool,
     alice: Vec<Texture2D>,
+    theme: Theme,
+    draw_tree: IncrementalDrawTree,
+    progress: SearchProgress,
+    /// Bound on the solution cost to show `progress.best_f` against, when
+    /// the heuristic makes best f a lower bound of the solution cost.
+    max_cost: Option<f64>,
+}
+
+/// Whether `point` is over the animated tree rather than the buttons above
+/// and below it, where a press starts dragging the view.
+fn over_anim_view((_, y): (i32, i32)) -> bool {
+    y > ANIM_BOUND.top + 20 && y < STOP_SEARCH_BUTTON.y as i32
+}
+
+/// One frame of follow mode: `offset` moved a quarter of the way to
+/// `target`, so the view glides after the expansions instead of jumping
+/// between far apart siblings, and lands on it once close.
+fn follow_offset(offset: (i32, i32), target: (i32, i32)) -> (i32, i32) {
+    let step = |from: i32, to: i32| match (to - from).abs() < 4 {
+        true => to,
+        false => from + (to - from) / 4,
+    };
+    (step(offset.0, target.0), step(offset.1, target.1))
+}
+
+/// Frame of Alice to draw out of `frames` with `open` boards in the open set,
+/// `None` when there are no frames to draw.
+fn alice_frame(frames: usize, open: usize) -> Option<usize> {
+    match frames {
+        0 => None,
+        _ => Some(open % frames),
+    }
 }
 
 struct AnimatingSearchTree<'handle: 'draw, 'draw, 'data> {
-    goal: &'data Puzzle,
-    initial: &'data Puzzle,
-    map: &'data HashMap<Puzzle, (Puzzle, i32)>,
     draw_handle: RaylibDrawHandle<'draw>,
     thread: PhantomData<&'handle RaylibThread>,
-    alice: &'data Texture2D,
+    /// `None` when no frame of Alice could be loaded.
+    alice: Option<&'data Texture2D>,
     max_nodes: &'data mut Cell<usize>,
+    cancelled: &'data Cell<bool>,
+    follow: &'data Cell<bool>,
+    theme: Theme,
 }
 
 impl<'handle: 'draw, 'draw, 'data> AnimatingSearchTree<'handle, 'draw, 'data> {
     fn from_animated_tree<'a: 'draw + 'data>(
         tree: &'a mut AnimatedSearchTree<'handle>,
-        alice_id: usize,
+        alice_id: Option<usize>,
     ) -> Self {
-        let AnimatedSearchTree {
-            goal,
-            initial,
-            map,
-            handle,
-            thread,
-            ..
-        } = tree;
+        let AnimatedSearchTree { handle, thread, .. } = tree;
         let mut draw_handle = RaylibHandle::begin_drawing(handle, thread);
-        draw_handle.clear_background(raylib::color::Color::WHITE);
+        draw_handle.clear_background(tree.theme.background);
         AnimatingSearchTree {
-            goal,
-            initial,
-            map,
             draw_handle,
             thread: PhantomData,
-            alice: &tree.alice[alice_id],
+            alice: alice_id.map(|id| &tree.alice[id]),
             max_nodes: &mut tree.max_nodes,
+            cancelled: &tree.cancelled,
+            follow: tree.follow,
+            theme: tree.theme,
         }
     }
 }
 
-impl AsMapSearchTree for AnimatingSearchTree<'_, '_, '_> {
-    fn goal(&self) -> &Puzzle {
-        self.goal
-    }
-    fn initial(&self) -> &Puzzle {
-        self.initial
-    }
-    fn map(&self) -> &HashMap<Puzzle, (Puzzle, i32)> {
-        self.map
-    }
-    fn map_mut(&mut self) -> &mut HashMap<Puzzle, (Puzzle, i32)> {
-        panic!("AnimatingSearchTree is read-only");
-    }
-    fn step_callback(&mut self, _: &Puzzle, _: (&Puzzle, bool), _: &OpenSet) {}
-}
-
-struct MixedIterativeSearchTree<'a, 'b, 'c, 'd> {
-    map_search_tree: &'d AnimatingSearchTree<'a, 'b, 'c>,
-    open_set: &'d OpenSet,
-}
-
-type ChainMapIter<'a, 'b> = Chain<
-    Map<
-        hash_map::Iter<'a, Puzzle, (Puzzle, i32)>,
-        fn((&'a Puzzle, &'a (Puzzle, i32))) -> (&'a Puzzle, &'a (Puzzle, i32), bool),
-    >,
-    Map<
-        hash_map::Iter<'b, Puzzle, (Puzzle, i32)>,
-        fn((&'b Puzzle, &'b (Puzzle, i32))) -> (&'b Puzzle, &'b (Puzzle, i32), bool),
-    >,
->;
-
-fn always_false<'a>(
-    (a, b): (&'a Puzzle, &'a (Puzzle, i32)),
-) -> (&'a Puzzle, &'a (Puzzle, i32), bool) {
-    (a, b, false)
-}
-fn always_true<'a>(
-    (a, b): (&'a Puzzle, &'a (Puzzle, i32)),
-) -> (&'a Puzzle, &'a (Puzzle, i32), bool) {
-    (a, b, true)
-}
-
-impl<'a, 'b: 'a, 'c: 'a, 'd: 'a, 'e: 'a> IterableSearchTree<'a, ChainMapIter<'a, 'a>>
-    for MixedIterativeSearchTree<'b, 'c, 'd, 'e>
-{
-    fn initial(&self) -> &Puzzle {
-        self.map_search_tree.initial()
-    }
-
-    fn goal(&self) -> &Puzzle {
-        self.map_search_tree.goal()
-    }
-
-    fn iter(&'a self) -> ChainMapIter<'a, 'a> {
-        let x = self.map_search_tree.map().iter().map(always_false as _);
-        x.chain(self.open_set.iter().map(always_true as _))
-    }
-}
-
 impl<'a> AsMapSearchTree for AnimatedSearchTree<'a> {
     fn goal(&self) -> &Puzzle {
         &self.goal
@@ -242,24 +417,98 @@ impl<'a> AsMapSearchTree for AnimatedSearchTree<'a> {
     fn map_mut(&mut self) -> &mut HashMap<Puzzle, (Puzzle, i32)> {
         &mut self.map
     }
-    fn step_callback(&mut self, current: &Puzzle, _: (&Puzzle, bool), open_set: &OpenSet) {
+    fn step_callback(&mut self, current: &Puzzle, (next, _): (&Puzzle, bool), open_set: &OpenSet) {
         if self.map.len() > self.max_nodes.get() {
             return;
         }
-        // draw alice in loop
+        // A push that lost to a cheaper path already in the open set leaves
+        // the tree as it was, and so would the frame.
+        let changed = open_set
+            .get(next)
+            .is_some_and(|entry| self.draw_tree.open(*next, entry));
+        if !changed {
+            return;
+        }
+        match self.pacing {
+            Some(pacing) => {
+                if self.progress.expanded < pacing.due(self.handle.get_time()) {
+                    return;
+                }
+            }
+            None => {
+                self.pending_steps += 1;
+                if self.pending_steps < self.steps_per_frame {
+                    return;
+                }
+                self.pending_steps = 0;
+            }
+        }
+
+        // draw alice in loop: she walks with the expanded nodes and
+        // her frame follows the size of the frontier
         let total = 1024 + ALICE_WIDTH;
         let single = total / 50;
         let left = (single * (self.map.len() as u32 % 50)) as i32 - ALICE_WIDTH as i32;
-        let alice_id = self.map.len() % self.alice.len();
+        let alice_id = alice_frame(self.alice.len(), open_set.len());
+        let root = self.draw_tree.root(current).clone();
+        let (progress, max_cost, pacing) = (self.progress, self.max_cost, self.pacing);
+        let fresh_frame = self.draw_tree.end_frame();
+        let fresh_frame = self.wavefront.then_some(fresh_frame);
+        let fringe = match self.show_fringe {
+            true => open_set
+                .iter_by_priority()
+                .take(FRINGE_SIZE)
+                .map(|(puzzle, f)| (*puzzle, f))
+                .collect(),
+            false => Vec::new(),
+        };
+        let bound = match fringe.is_empty() {
+            true => ANIM_BOUND,
+            false => ANIM_BOUND_WITH_FRINGE,
+        };
+        let sizer = PuzzleSizer { scale: 3.0 };
+        let mouse = (self.handle.get_mouse_x(), self.handle.get_mouse_y());
+        match self.follow.get() {
+            true => {
+                self.drag_from = None;
+                root.build_coord(&sizer);
+                if let Some(node) = self.draw_tree.node(current) {
+                    let node = node.borrow();
+                    // Screen position of the node is the offset plus its
+                    // place in the tree, so the center minus its place
+                    // puts it in the middle.
+                    let center = ((bound.left + bound.right) / 2, (bound.top + 768) / 2);
+                    let target = (
+                        center.0 - node.center_x,
+                        center.1 - sizer.depth_y(node.depth),
+                    );
+                    self.view_offset = follow_offset(self.view_offset, target);
+                }
+            }
+            false => {
+                let dragging = self
+                    .handle
+                    .is_mouse_button_down(raylib::consts::MouseButton::MOUSE_BUTTON_LEFT)
+                    && (self.drag_from.is_some() || over_anim_view(mouse));
+                if let (true, Some(from)) = (dragging, self.drag_from) {
+                    self.view_offset.0 += mouse.0 - from.0;
+                    self.view_offset.1 += mouse.1 - from.1;
+                }
+                self.drag_from = dragging.then_some(mouse);
+            }
+        }
+        let view_offset = self.view_offset;
 
         let mut animating = AnimatingSearchTree::from_animated_tree(self, alice_id);
 
-        animating.draw_handle.draw_texture(
-            animating.alice,
-            left as i32,
-            768 - ALICE_HEIGHT as i32,
-            raylib::color::Color::WHITE,
-        );
+        if let Some(alice) = animating.alice {
+            animating.draw_handle.draw_texture(
+                alice,
+                left as i32,
+                768 - ALICE_HEIGHT as i32,
+                raylib::color::Color::WHITE,
+            );
+        }
 
         if animating.draw_handle.gui_button(
             Rectangle {
@@ -272,19 +521,108 @@ impl<'a> AsMapSearchTree for AnimatedSearchTree<'a> {
         ) {
             animating.max_nodes.set(0);
         }
-
-        let mixed_iterative = MixedIterativeSearchTree {
-            map_search_tree: &animating,
-            open_set,
+        if animating
+            .draw_handle
+            .gui_button(STOP_SEARCH_BUTTON, Some(rstr!("Stop search")))
+        {
+            animating.cancelled.set(true);
+        }
+        let follow_label = match animating.follow.get() {
+            true => rstr!("Follow: on"),
+            false => rstr!("Follow: off"),
         };
-        let (a, _) = RcRefDrawTreeNode::new_from_map_search_tree(&mixed_iterative, current);
+        if animating
+            .draw_handle
+            .gui_button(FOLLOW_BUTTON, Some(follow_label))
+        {
+            animating.follow.set(!animating.follow.get());
+        }
+
         let mut painter = ElementPainter {
             draw_handle: &mut animating.draw_handle,
-            bound: ANIM_BOUND,
-            offset: (1024 / 2, ANIM_BOUND.top + 20),
-            sizer: PuzzleSizer { scale: 3 },
+            bound,
+            offset: view_offset,
+            sizer,
+            theme: animating.theme,
+            max_render_depth: None,
+            edge_thickness: EDGE_THICKNESS,
+            g_ramp: None,
+            f_ramp: None,
+            sources: None,
+            fresh_frame,
+            ghosts: false,
+            max_edges: None,
+        };
+        root.draw(&mut painter);
+
+        if !fringe.is_empty() {
+            draw_fringe_panel(
+                &mut animating.draw_handle,
+                &animating.theme,
+                ANIM_BOUND_WITH_FRINGE.right + 10,
+                ANIM_BOUND.top,
+                &fringe,
+            );
+        }
+
+        // Drawing stops at `max_nodes`, the animation is done when the
+        // search is or when this bar is full.
+        let (limit, animating_text) = match pacing {
+            Some(pacing) => (
+                pacing.total,
+                format!(
+                    "Animating {} nodes over {:.0} s",
+                    pacing.total, pacing.duration
+                ),
+            ),
+            None => {
+                let limit = animating.max_nodes.get();
+                (
+                    limit,
+                    format!("Animating up to {} nodes, then fast-forward", limit),
+                )
+            }
         };
-        a.draw(&mut painter);
+        animating
+            .draw_handle
+            .draw_text(&animating_text, 110, 5, 10, animating.theme.text);
+        let nodes_text = CString::new(format!("{}/{}", progress.expanded, limit)).unwrap();
+        animating.draw_handle.gui_progress_bar(
+            NODES_PROGRESS_BAR,
+            Some(rstr!("Nodes")),
+            Some(nodes_text.as_c_str()),
+            &mut (progress.of_limit(limit) as f32),
+            0.0,
+            1.0,
+        );
+        if let Some(max_cost) = max_cost {
+            // Approximate: how far best f has climbed towards the most moves
+            // any board needs, not towards the unknown depth of this one.
+            let f_text =
+                CString::new(format!("f {:.0} of {:.0} max", progress.best_f, max_cost)).unwrap();
+            animating.draw_handle.gui_progress_bar(
+                DEPTH_PROGRESS_BAR,
+                Some(rstr!("Depth ~")),
+                Some(f_text.as_c_str()),
+                &mut (progress.of_cost(max_cost) as f32),
+                0.0,
+                1.0,
+            );
+        }
+    }
+
+    fn on_close(&mut self, key: &Puzzle, value: (Puzzle, i32)) {
+        if self.map.len() <= self.max_nodes.get() {
+            self.draw_tree.close(*key, value);
+        }
+    }
+
+    fn on_progress(&mut self, progress: &SearchProgress) {
+        self.progress = *progress;
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
     }
 }
 
@@ -299,11 +637,20 @@ impl<T: AsMapSearchTree> SearchTree for MapSearchTree<'_, T> {
 
     fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
         self.map_mut().insert(key, value);
+        self.inner.on_close(&key, value);
     }
 
     fn step_callback(&mut self, current: &Puzzle, next: (&Puzzle, bool), open_set: &OpenSet) {
         self.inner.step_callback(current, next, open_set);
     }
+
+    fn on_progress(&mut self, progress: &SearchProgress) {
+        self.inner.on_progress(progress);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
 }
 
 const SET_GOAL_BUTTON: Rectangle = Rectangle {
@@ -348,6 +695,286 @@ const STRATEGY_LIST: Rectangle = Rectangle {
     height: 24.0,
 };
 
+const ROOT_BUTTON: Rectangle = Rectangle {
+    x: 460.0,
+    y: 140.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const THEME_BUTTON: Rectangle = Rectangle {
+    x: 460.0,
+    y: 110.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const STEPS_LIST: Rectangle = Rectangle {
+    x: 460.0,
+    y: 170.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const STEPS_PER_FRAME: [usize; 4] = [1, 4, 16, 64];
+
+const AUTO_PLAY_BUTTON: Rectangle = Rectangle {
+    x: 830.0,
+    y: 140.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const AUTO_PLAY_INTERVAL_LIST: Rectangle = Rectangle {
+    x: 925.0,
+    y: 140.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const PLAY_BUTTON: Rectangle = Rectangle {
+    x: 290.0,
+    y: 140.0,
+    width: 55.0,
+    height: 24.0,
+};
+
+const HINTS_BUTTON: Rectangle = Rectangle {
+    x: 140.0,
+    y: 140.0,
+    width: 55.0,
+    height: 24.0,
+};
+
+const SOLVE_PLAY_BUTTON: Rectangle = Rectangle {
+    x: 290.0,
+    y: 112.0,
+    width: 55.0,
+    height: 24.0,
+};
+
+const F_BOARDS_BUTTON: Rectangle = Rectangle {
+    x: 105.0,
+    y: 8.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const G_EDGES_BUTTON: Rectangle = Rectangle {
+    x: 925.0,
+    y: 80.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const WAVEFRONT_BUTTON: Rectangle = Rectangle {
+    x: 830.0,
+    y: 170.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const PARITY_BUTTON: Rectangle = Rectangle {
+    x: 830.0,
+    y: 80.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const STEP_BACK_BUTTON: Rectangle = Rectangle {
+    x: 145.0,
+    y: 170.0,
+    width: 65.0,
+    height: 24.0,
+};
+const STEP_FORWARD_BUTTON: Rectangle = Rectangle {
+    x: 215.0,
+    y: 170.0,
+    width: 65.0,
+    height: 24.0,
+};
+const GHOSTS_BUTTON: Rectangle = Rectangle {
+    x: 50.0,
+    y: 170.0,
+    width: 85.0,
+    height: 24.0,
+};
+const LAYOUT_BUTTON: Rectangle = Rectangle {
+    x: 755.0,
+    y: 170.0,
+    width: 70.0,
+    height: 24.0,
+};
+const FRINGE_BUTTON: Rectangle = Rectangle {
+    x: 925.0,
+    y: 170.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const SAVE_SEARCH_BUTTON: Rectangle = Rectangle {
+    x: 830.0,
+    y: 20.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const REPLAY_SEARCH_BUTTON: Rectangle = Rectangle {
+    x: 925.0,
+    y: 20.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const EXPORT_TIKZ_BUTTON: Rectangle = Rectangle {
+    x: 830.0,
+    y: 110.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+/// Board size of TikZ exports, large enough for the tile numbers to fit.
+const TIKZ_SIZER: PuzzleSizer = PuzzleSizer { scale: 6.0 };
+
+/// Smallest tree scale, one pixel per tile.
+const MIN_SCALE: f32 = 1.0;
+/// Tree scale a solve with each strategy of `STRATEGY_LIST` starts out at.
+/// BFS trees are wide and only fit zoomed out, the better the heuristic the
+/// narrower the tree and the larger its boards can be drawn.
+const STRATEGY_SCALES: [f32; 5] = [1.0, 2.0, 2.5, 3.0, 3.0];
+/// Scale change per notch of the mouse wheel.
+const ZOOM_STEP: f32 = 0.25;
+
+const COMPARE_BUTTON: Rectangle = Rectangle {
+    x: 600.0,
+    y: 170.0,
+    width: 80.0,
+    height: 24.0,
+};
+
+const TILE_DISTANCES_BUTTON: Rectangle = Rectangle {
+    x: 200.0,
+    y: 8.0,
+    width: 85.0,
+    height: 24.0,
+};
+const PARITY_CLASSES_BUTTON: Rectangle = Rectangle {
+    x: 685.0,
+    y: 170.0,
+    width: 65.0,
+    height: 24.0,
+};
+/// Top left corner of the parity classes panel, over the tree.
+const PARITY_PANEL: (i32, i32) = (MAIN_BOUND.left + 30, 240);
+const MOVE_LIST_BUTTON: Rectangle = Rectangle {
+    x: 290.0,
+    y: 8.0,
+    width: 90.0,
+    height: 24.0,
+};
+const NEXT_STRATEGY_BUTTON: Rectangle = Rectangle {
+    x: 485.0,
+    y: 8.0,
+    width: 90.0,
+    height: 24.0,
+};
+const SHARE_BUTTON: Rectangle = Rectangle {
+    x: 580.0,
+    y: 200.0 + 10.0,
+    width: 40.0,
+    height: 24.0,
+};
+/// Top left corner of the share panel, over the tree between the parity
+/// classes and the comparison.
+const SHARE_PANEL: (i32, i32) = (350, 240);
+const PAN_BUTTON: Rectangle = Rectangle {
+    x: 390.0,
+    y: 8.0,
+    width: 90.0,
+    height: 24.0,
+};
+/// Top left corner of the move list, on the left of the tree below the
+/// parity classes panel.
+const MOVE_LIST_PANEL: (i32, i32) = (MAIN_BOUND.left + 30, 400);
+
+const AUTO_PLAY_INTERVALS: [f64; 3] = [0.25, 0.5, 1.0];
+
+const RENDER_DEPTH_LIST: Rectangle = Rectangle {
+    x: 1024.0 - (10.0 + 24.0) * 2.0 - 110.0,
+    y: 200.0 + 10.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const UNSOLVED_BUTTON: Rectangle = Rectangle {
+    x: 925.0,
+    y: 110.0,
+    width: 90.0,
+    height: 24.0,
+};
+
+const CENTER_BUTTON: Rectangle = Rectangle {
+    x: 1024.0 - (10.0 + 24.0) * 2.0 - 110.0 * 2.0,
+    y: 200.0 + 10.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const MOVE_ORDER_LIST: Rectangle = Rectangle {
+    x: 1024.0 - (10.0 + 24.0) * 2.0 - 110.0 * 3.0,
+    y: 200.0 + 10.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+/// Orders the blank moves are tried in, matching `MOVE_ORDER_LIST`.
+const MOVE_ORDERS: [[Direction; 4]; 4] = [
+    Direction::all(),
+    [
+        Direction::Right,
+        Direction::Left,
+        Direction::Down,
+        Direction::Up,
+    ],
+    [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ],
+    [
+        Direction::Down,
+        Direction::Up,
+        Direction::Right,
+        Direction::Left,
+    ],
+];
+
+const G_WEIGHT_SLIDER: Rectangle = Rectangle {
+    x: 70.0,
+    y: 200.0 + 10.0,
+    width: 100.0,
+    height: 24.0,
+};
+const H_WEIGHT_SLIDER: Rectangle = Rectangle {
+    x: 240.0,
+    y: 200.0 + 10.0,
+    width: 100.0,
+    height: 24.0,
+};
+const MAX_WEIGHT: f32 = 3.0;
+
+const ANIM_NODES_BOX: Rectangle = Rectangle {
+    x: 480.0,
+    y: 200.0 + 10.0,
+    width: 60.0,
+    height: 24.0,
+};
+/// Boards drawn one by one before the animation skips to the result.
+const DEFAULT_ANIM_NODES: i32 = 300;
+const MAX_ANIM_NODES: i32 = 100_000;
+const RENDER_DEPTHS: [Option<u32>; 5] = [None, Some(5), Some(10), Some(20), Some(40)];
+
 const PLUS_BUTTON: Rectangle = Rectangle {
     x: 1024.0 - (10.0 + 24.0),
     y: 200.0 + 10.0,
@@ -369,22 +996,155 @@ const MAIN_BOUND: IntRectBound = IntRectBound {
     bottom: 800,
 };
 
-const ANIM_BOUND: IntRectBound = IntRectBound {
-    left: 0 + 10,
-    top: 10,
-    right: 1024 - 10,
-    bottom: 800,
+const NODES_PROGRESS_BAR: Rectangle = Rectangle {
+    x: 390.0,
+    y: 0.0,
+    width: 150.0,
+    height: 20.0,
 };
 
-fn main() {
-    let (mut handle, thread) = raylib::init().title("sliding-puzzle").size(1024, 768).build();
-    let mut goal = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
-    let mut initial = Puzzle::new([[1, 3, 4], [8, 2, 5], [0, 7, 6]]);
-    let mut setting_goal: Option<SetPuzzle> = None;
-    let mut setting_initial: Option<SetPuzzle> = None;
+const DEPTH_PROGRESS_BAR: Rectangle = Rectangle {
+    x: 680.0,
+    y: 0.0,
+    width: 150.0,
+    height: 20.0,
+};
+
+/// Bottom right of the animation, clear of the progress bars and the fringe
+/// panel.
+const FOLLOW_BUTTON: Rectangle = Rectangle {
+    x: 1024.0 - 10.0 - 100.0 - 5.0 - 100.0,
+    y: 768.0 - 10.0 - 24.0,
+    width: 100.0,
+    height: 24.0,
+};
+
+const STOP_SEARCH_BUTTON: Rectangle = Rectangle {
+    x: 1024.0 - 10.0 - 100.0,
+    y: 768.0 - 10.0 - 24.0,
+    width: 100.0,
+    height: 24.0,
+};
+const ANIM_BOUND: IntRectBound = IntRectBound {
+    left: 0 + 10,
+    top: 10,
+    right: 1024 - 10,
+    bottom: 800,
+};
+
+const ANIM_BOUND_WITH_FRINGE: IntRectBound = IntRectBound {
+    left: 0 + 10,
+    top: 10,
+    right: 1024 - 120,
+    bottom: 800,
+};
+
+/// Screen position of the root of the solved tree before any panning.
+const TREE_ORIGIN: (i32, i32) = (500, 220);
+
+/// Arrow keys and the moves of the tree selection they make.
+const TREE_KEYS: [(KeyboardKey, TreeStep); 4] = [
+    (KeyboardKey::KEY_UP, TreeStep::Parent),
+    (KeyboardKey::KEY_DOWN, TreeStep::FirstChild),
+    (KeyboardKey::KEY_LEFT, TreeStep::PreviousSibling),
+    (KeyboardKey::KEY_RIGHT, TreeStep::NextSibling),
+];
+
+const FRINGE_SIZE: usize = 20;
+
+/// Animation speed of demo rounds when the animation is turned off, 20 FPS.
+const DEMO_FPS_X5: i32 = 4;
+
+const EDGE_THICKNESS: f32 = 0.5;
+
+/// Most tree edges drawn per frame unless `--max-edges` says otherwise.
+const DEFAULT_MAX_EDGES: usize = 20_000;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|arg| arg == "--bench") {
+        let path = args.get(i + 1).map_or("bench.csv", String::as_str);
+        run_bench(path);
+        return;
+    }
+    // `--demo [idle seconds]` turns on the kiosk loop.
+    let demo_timeout = args.iter().position(|arg| arg == "--demo").map(|i| {
+        args.get(i + 1)
+            .and_then(|seconds| seconds.parse().ok())
+            .unwrap_or(DEFAULT_IDLE_TIMEOUT)
+    });
+    // `--scramble "U L L D R"` starts from the goal scrambled by those moves.
+    let scrambled = match args.iter().position(|arg| arg == "--scramble") {
+        Some(i) => {
+            let moves = args.get(i + 1).map_or("", String::as_str);
+            match GoalPreset::Spiral.puzzle().scrambled(moves) {
+                Ok(board) => Some(board),
+                Err(e) => {
+                    println!("Cannot scramble the goal with \"{}\": {}", moves, e);
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+    // `--max-edges N` caps the tree edges drawn per frame, 0 draws all.
+    let max_edges = match args.iter().position(|arg| arg == "--max-edges") {
+        Some(i) => match args.get(i + 1).map(|count| count.parse::<usize>()) {
+            Some(Ok(0)) => None,
+            Some(Ok(count)) => Some(count),
+            _ => {
+                println!("--max-edges takes a number of edges, 0 for no cap");
+                return;
+            }
+        },
+        None => Some(DEFAULT_MAX_EDGES),
+    };
+    // `--animate-for SECONDS` stretches or squeezes every animated search to
+    // last that long, see `Pacing`.
+    let animate_for = match args.iter().position(|arg| arg == "--animate-for") {
+        Some(i) => match args.get(i + 1).map(|seconds| seconds.parse::<f64>()) {
+            Some(Ok(seconds)) if seconds > 0.0 => Some(seconds),
+            _ => {
+                println!("--animate-for takes a positive number of seconds");
+                return;
+            }
+        },
+        None => None,
+    };
+    let initial = scrambled.unwrap_or(Puzzle::new([[1, 3, 4], [8, 2, 5], [0, 7, 6]]));
+    // `--stats [strategy]` prints the counters of one solve of `initial` as
+    // JSON and exits. `--deterministic` leaves out the time.
+    if let Some(i) = args.iter().position(|arg| arg == "--stats") {
+        let strategy = args
+            .get(i + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .map_or("Manhattan", String::as_str);
+        let deterministic = args.iter().any(|arg| arg == "--deterministic");
+        run_stats(initial, strategy, deterministic);
+        return;
+    }
+    // `--count [strategy]` prints only the solution length of `initial` and
+    // the nodes it took, without keeping the search tree.
+    if let Some(i) = args.iter().position(|arg| arg == "--count") {
+        let strategy = args
+            .get(i + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .map_or("Manhattan", String::as_str);
+        run_count(initial, strategy);
+        return;
+    }
+
+    let (mut handle, thread) = raylib::init().title("sliding-puzzle").size(1024, 768).build();
+    let mut goal = GoalPreset::Spiral.puzzle();
+    let mut initial = initial;
+    let mut setting_goal: Option<SetPuzzle> = None;
+    let mut setting_initial: Option<SetPuzzle> = None;
+    let board_sizes = BoardSizes::MAIN;
 
     let mut show_result = false;
+    let mut solved_tree: Option<NativeSearchTree> = None;
     let mut solution_tree: Option<((RcRefDrawTreeNode, Option<RcRefDrawTreeNode>), usize)> = None;
+    let mut tree_root = TreeRoot::Initial;
 
     let mut offset_xy = (0, 0);
     let mut offset_xy_old = (0, 0);
@@ -392,20 +1152,118 @@ fn main() {
 
     handle.gui_enable();
 
+    let strategy_names =
+        CString::new(Strategy::all().map(|strategy| strategy.name()).join(";")).unwrap();
     let mut selected_strategy = 0;
+    let mut solved_strategy = Strategy::all()[0];
+    let mut strategy_runs: HashMap<Strategy, StrategyRun> = HashMap::new();
     let mut strategy_edit = false;
     let mut animate_fps_x5: i32 = 0;
+    let mut anim_nodes = DEFAULT_ANIM_NODES;
+    let mut anim_nodes_edit = false;
     let mut animation_edit = false;
-    let mut display_scale = 2;
+    let mut steps_per_frame_id: i32 = 0;
+    let mut steps_edit = false;
+    let mut show_fringe = false;
+    let mut wavefront = false;
+    let mut layout = TreeLayout::Tidy;
+    let mut check_parity = true;
+    let mut tile_flash = TileFlash::default();
+    let mut random_seed: Option<(u64, Puzzle)> = None;
+    let mut report: Option<Report> = None;
+    let mut parity_explorer: Option<ParityExplorer> = None;
+    let mut move_list: Option<MoveList> = None;
+    let mut share_panel: Option<SharePanel> = None;
+    let mut session = Session::load(SESSION_PATH).unwrap_or_else(|e| {
+        println!("Cannot load session, using defaults: {}", e);
+        Session::default()
+    });
+    let mut show_tile_distances = false;
+    let mut replay: Option<Replay> = None;
+    let mut play_mode: Option<PlayMode> = None;
+    let mut oracle: Option<Oracle> = None;
+    let mut show_hints = true;
+    // Toggled from the animation, whose tree borrows it.
+    let follow_search = Cell::new(true);
+    let mut display_scale: f32 = 2.0;
+    let mut render_depth_id: i32 = 0;
+    let mut render_depth_edit = false;
+    let mut center_on = CenterOn::Goal;
+    let mut keep_unsolved = false;
+    let mut move_order_id: i32 = 0;
+    let mut move_order_edit = false;
+    let mut g_weight: f32 = 1.0;
+    let mut h_weight: f32 = 1.0;
+    let mut solved_weights = CostWeights::default();
+    let mut solve_failure: Option<String> = None;
+    let mut solve_stats: Option<SearchStats> = None;
+    let mut theme = Theme::LIGHT;
+    let mut library_panel = LibraryPanel::load();
+    let mut auto_play: Option<AutoPlay> = None;
+    let mut auto_play_interval_id: i32 = 1;
+    let mut auto_play_interval_edit = false;
+    let mut demo = demo_timeout.map(|timeout| Demo::new(timeout, &handle));
+    let mut selected_node: Option<RcRefDrawTreeNode> = None;
+    let mut color_by_g = false;
+    let mut board_colors = BoardColors::Plain;
+    // f of every board of `solved_tree`, for `BoardColors::F`.
+    let mut f_values: Option<FValues> = None;
+    // Winning part of the heuristic for every board of `solved_tree`, for
+    // `BoardColors::Source`. `None` unless it was searched with a combined
+    // heuristic.
+    let mut source_values: Option<HeuristicSources> = None;
+    let mut show_ghosts = true;
+    let mut tree_max_g = 0;
 
     while !handle.window_should_close() {
+        let mut demo_round = None;
+        if let Some(demo) = &mut demo {
+            demo.read_input(&mut handle);
+            let round_finished = auto_play.as_ref().is_none_or(AutoPlay::is_finished);
+            demo_round = demo.next_round(handle.get_time(), round_finished, &goal);
+        }
+        if let Some(round) = &demo_round {
+            initial = round.board;
+            random_seed = Some((round.seed, round.board));
+            selected_strategy = strategy_index(round.strategy) as i32;
+            setting_goal = None;
+            setting_initial = None;
+            play_mode = None;
+            replay = None;
+        }
+
+        let over_parity_panel = parity_explorer.is_some()
+            && ParityExplorer::bounds(PARITY_PANEL.0, PARITY_PANEL.1)
+                .check_collision_point_rec(handle.get_mouse_position());
+        let over_share_panel = share_panel.is_some()
+            && SharePanel::bounds(SHARE_PANEL.0, SHARE_PANEL.1)
+                .check_collision_point_rec(handle.get_mouse_position());
+        let typing =
+            library_panel.is_typing() || share_panel.as_ref().is_some_and(SharePanel::is_typing);
+        let over_move_list = show_result
+            && move_list.is_some()
+            && MoveList::bounds(MOVE_LIST_PANEL.0, MOVE_LIST_PANEL.1)
+                .check_collision_point_rec(handle.get_mouse_position());
         if handle.is_mouse_button_down(raylib::consts::MouseButton::MOUSE_BUTTON_LEFT) {
             if let Some(start) = start_pos {
+                // Inverted, the drag moves the view and the tree goes the
+                // other way.
+                let sign = match session.invert_pan {
+                    true => -1,
+                    false => 1,
+                };
                 offset_xy = (
-                    handle.get_mouse_x() - start.0 + offset_xy_old.0,
-                    handle.get_mouse_y() - start.1 + offset_xy_old.1,
+                    sign * (handle.get_mouse_x() - start.0) + offset_xy_old.0,
+                    sign * (handle.get_mouse_y() - start.1) + offset_xy_old.1,
                 );
-            } else if handle.get_mouse_y() > 200 {
+            } else if handle.get_mouse_y() > 200
+                && !G_WEIGHT_SLIDER.check_collision_point_rec(handle.get_mouse_position())
+                && !H_WEIGHT_SLIDER.check_collision_point_rec(handle.get_mouse_position())
+                && !ANIM_NODES_BOX.check_collision_point_rec(handle.get_mouse_position())
+                && !over_parity_panel
+                && !over_move_list
+                && !over_share_panel
+            {
                 start_pos = Some((handle.get_mouse_x(), handle.get_mouse_y()));
                 offset_xy_old = offset_xy;
             }
@@ -413,32 +1271,148 @@ fn main() {
             start_pos = None;
         }
 
+        let wheel = handle.get_mouse_wheel_move();
+        if show_result && wheel != 0.0 && handle.get_mouse_y() > MAIN_BOUND.top && !over_move_list {
+            display_scale = (display_scale + wheel * ZOOM_STEP).max(MIN_SCALE);
+        }
+
+        if !show_result {
+            auto_play = None;
+        }
+
+        let tree_step = TREE_KEYS
+            .iter()
+            .find(|(key, _)| handle.is_key_pressed(*key))
+            .map(|&(_, step)| step);
+        if let (Some(step), true, Some(((root, _), _))) =
+            (tree_step, show_result && !typing, &solution_tree)
+        {
+            selected_node = match &selected_node {
+                Some(node) => root.step_from(node, step).or(selected_node),
+                None => Some(root.clone()),
+            };
+            if let Some(node) = &selected_node {
+                scroll_into_view(
+                    node,
+                    &PuzzleSizer {
+                        scale: display_scale,
+                    },
+                    &mut offset_xy,
+                );
+            }
+        }
+
+        let old_tree_root = tree_root;
+        let old_center_on = center_on;
+        let old_theme_dark = theme.dark;
+        let mut solve_play = false;
+        let mut replay_stepped = false;
+        let mut try_next_strategy = false;
+        let mut solve_shared = false;
+
         let request_solve = {
             let mut draw_handle = handle.begin_drawing(&thread);
-            draw_handle.clear_background(raylib::color::Color::WHITE);
+            draw_handle.clear_background(theme.background);
+
+            let mut hovered = None;
 
             if show_result {
                 if let Some(((solution, _), _)) = &solution_tree {
                     let mut painter = ElementPainter {
                         draw_handle: &mut draw_handle,
                         bound: MAIN_BOUND,
-                        offset: (500 + offset_xy.0, 220 + offset_xy.1),
+                        offset: (TREE_ORIGIN.0 + offset_xy.0, TREE_ORIGIN.1 + offset_xy.1),
                         sizer: PuzzleSizer {
                             scale: display_scale,
                         },
+                        theme,
+                        max_render_depth: RENDER_DEPTHS[render_depth_id as usize],
+                        edge_thickness: EDGE_THICKNESS,
+                        g_ramp: color_by_g.then_some(tree_max_g),
+                        f_ramp: f_values.as_ref().filter(|_| board_colors == BoardColors::F),
+                        sources: source_values
+                            .as_ref()
+                            .filter(|_| board_colors == BoardColors::Source),
+                        fresh_frame: None,
+                        ghosts: show_ghosts,
+                        max_edges,
                     };
-                    solution.draw(&mut painter);
+                    let omitted_edges = solution.draw(&mut painter);
+                    painter.draw_depth_axis();
+                    if color_by_g {
+                        draw_g_legend(
+                            painter.draw_handle,
+                            &theme,
+                            tree_max_g,
+                            MAIN_BOUND.left + 30,
+                            768 - 44,
+                        );
+                    }
+                    match (board_colors, &f_values, &source_values) {
+                        (BoardColors::F, Some(f_values), _) => draw_f_legend(
+                            painter.draw_handle,
+                            &theme,
+                            f_values.range(),
+                            MAIN_BOUND.left + 150,
+                            768 - 44,
+                        ),
+                        (BoardColors::Source, _, Some(sources)) => draw_source_legend(
+                            painter.draw_handle,
+                            &theme,
+                            sources.names(),
+                            MAIN_BOUND.left + 150,
+                            768 - 44,
+                        ),
+                        _ => {}
+                    }
+                    // Drawn on screen coordinates, it stays put while the
+                    // tree pans and zooms under it.
+                    let searched_goal = solved_tree.as_ref().map_or(goal, |tree| tree.goal);
+                    draw_pinned_goal(
+                        painter.draw_handle,
+                        &theme,
+                        &searched_goal,
+                        MAIN_BOUND.right - 10,
+                        768 - 10,
+                    );
+                    if omitted_edges > 0 {
+                        let text = format!("{} edges not drawn", omitted_edges);
+                        painter.draw_handle.draw_text(
+                            &text,
+                            MAIN_BOUND.right - 10 - measure_text(&text, 10),
+                            768 - 92,
+                            10,
+                            theme.edge,
+                        );
+                    }
+
+                    if let Some(node) = &selected_node {
+                        painter.draw_selection(node);
+                        hovered = painter
+                            .node_position(node)
+                            .map(|position| (node.clone(), position));
+                    }
+
+                    let mouse = (
+                        painter.draw_handle.get_mouse_x(),
+                        painter.draw_handle.get_mouse_y(),
+                    );
+                    if mouse.1 > MAIN_BOUND.top && start_pos.is_none() {
+                        hovered = solution
+                            .node_at(&painter, mouse.0, mouse.1)
+                            .map(|node| (node, mouse));
+                    }
                 }
             }
 
-            draw_handle.draw_rectangle(0, 0, 1100, 200, raylib::color::Color::RAYWHITE);
+            draw_handle.draw_rectangle(0, 0, 1100, 200, theme.panel);
 
             if animation_edit {
                 draw_handle.gui_lock();
             }
             if draw_handle.gui_dropdown_box(
                 STRATEGY_LIST,
-                Some(rstr!("BFS;A* (1);A* (2)")),
+                Some(strategy_names.as_c_str()),
                 &mut selected_strategy,
                 strategy_edit,
             ) {
@@ -457,20 +1431,71 @@ fn main() {
                 animation_edit = !animation_edit;
             }
 
+            if draw_handle.gui_dropdown_box(
+                STEPS_LIST,
+                Some(rstr!("1 step;4 steps;16 steps;64 steps")),
+                &mut steps_per_frame_id,
+                steps_edit,
+            ) {
+                steps_edit = !steps_edit;
+            }
+
             // show result
             if show_result {
-                if let Some(((_, goal_node), count)) = &solution_tree {
+                if let Some(((_, Some(goal_node)), count)) = &solution_tree {
                     draw_handle.draw_text(
                         &*format!(
                             "Solution found, {} nodes, take {} step(s).",
                             count,
-                            goal_node.as_ref().unwrap().borrow().depth
+                            goal_node.borrow().depth
                         ),
                         500,
                         52,
                         20,
                         raylib::color::Color::GREEN,
                     );
+                    draw_handle.draw_text(
+                        &solution_len_text(goal_node.borrow().depth as usize),
+                        630,
+                        35,
+                        10,
+                        theme.text,
+                    );
+                    if let (Some(stats), None) = (solve_stats, &replay) {
+                        draw_handle.draw_text(
+                            &format!("took {:.2} s", stats.elapsed.as_secs_f64()),
+                            700,
+                            35,
+                            10,
+                            theme.text,
+                        );
+                    }
+                    let optimal = SolveConfig::new(solved_strategy)
+                        .weights(solved_weights)
+                        .proves_optimal();
+                    match (&replay, optimal) {
+                        (Some(_), _) => {}
+                        (None, true) => {
+                            draw_badge(&mut draw_handle, 500, 32, "optimal", Color::DARKGREEN)
+                        }
+                        (None, false) => draw_badge(
+                            &mut draw_handle,
+                            500,
+                            32,
+                            "possibly non-optimal",
+                            Color::ORANGE,
+                        ),
+                    }
+                } else if let Some(message) = &solve_failure {
+                    draw_handle.draw_text(message, 500, 52, 20, raylib::color::Color::RED);
+                } else if let Some((_, count)) = &solution_tree {
+                    draw_handle.draw_text(
+                        &format!("Explored {} nodes, goal not reached.", count),
+                        500,
+                        52,
+                        20,
+                        theme.text,
+                    );
                 } else {
                     draw_handle.draw_text(
                         "No solution found",
@@ -482,30 +1507,531 @@ fn main() {
                 }
             }
 
-            draw_handle.draw_text(AUTHOR_NOTE, 500, 82, 20, raylib::color::Color::DARKCYAN);
+            draw_handle.draw_text(AUTHOR_NOTE, 500, 82, 20, theme.accent);
+            if demo.as_ref().is_some_and(Demo::is_running) {
+                draw_handle.draw_text("Demo, touch anything to stop", 50, 5, 10, theme.accent);
+            }
+            draw_handle.draw_text(
+                &describe_strategy(Strategy::all()[selected_strategy as usize]),
+                350,
+                20,
+                10,
+                theme.text,
+            );
+
+            let goal_name = GoalPreset::classify(&goal).map_or("custom", |preset| preset.name());
+            draw_handle.draw_text(&format!("Goal: {}", goal_name), 50, 36, 10, theme.text);
 
             if let Some(set_goal) = &mut setting_goal {
-                set_goal.read_event(&draw_handle);
-                set_goal.draw(&mut draw_handle, 50, 50);
+                if !typing {
+                    set_goal.read_event(&draw_handle);
+                }
+                set_goal.draw(&mut draw_handle, &theme, 50, 50, board_sizes);
 
                 if let Some(puzzle) = set_goal.get_puzzle() {
                     goal = puzzle;
                     setting_goal = None;
                 }
             } else {
-                draw_puzzle(&mut draw_handle, &goal, 50, 50);
+                draw_puzzle(&mut draw_handle, &theme, &goal, 50, 50, board_sizes);
             }
 
             if let Some(set_initial) = &mut setting_initial {
-                set_initial.read_event(&draw_handle);
-                set_initial.draw(&mut draw_handle, 200, 50);
+                if !typing {
+                    set_initial.read_event(&draw_handle);
+                }
+                set_initial.draw(&mut draw_handle, &theme, 200, 50, board_sizes);
 
                 if let Some(puzzle) = set_initial.get_puzzle() {
                     initial = puzzle;
                     setting_initial = None;
                 }
+            } else if let Some(play) = &mut play_mode {
+                if *play.board() != initial {
+                    *play = PlayMode::new(initial);
+                }
+                if !typing {
+                    play.read_event(&draw_handle, 200, 50, board_sizes);
+                }
+                let oracle = match &mut oracle {
+                    Some(oracle) if *oracle.goal() == goal => oracle,
+                    _ => oracle.insert(Oracle::new(goal)),
+                };
+                oracle.update();
+                play.update(draw_handle.get_time(), &mut tile_flash, oracle.map());
+                if *play.board() != initial {
+                    initial = *play.board();
+                    show_result = false;
+                }
+
+                draw_puzzle(&mut draw_handle, &theme, play.board(), 200, 50, board_sizes);
+                tile_flash.draw(&mut draw_handle, 200, 50, board_sizes);
+                if let (true, Some(map), false) = (show_hints, oracle.map(), typing) {
+                    play.draw_hint(&mut draw_handle, map, 200, 50, board_sizes);
+                }
+                let status = match *play.board() == goal {
+                    true => "Already solved".to_string(),
+                    false => format!("{} moves", play.moves()),
+                };
+                draw_handle.draw_text(&status, 290, 172, 10, theme.text);
+                let tally = play.tally();
+                let judged = match oracle.map() {
+                    Some(_) => format!("{} optimal, {} detours", tally.optimal, tally.detours),
+                    None => format!("Oracle {:.0}%", oracle.progress() * 100.0),
+                };
+                draw_handle.draw_text(&judged, 290, 186, 10, theme.text);
+            } else if let Some(play) = &mut auto_play {
+                play.update(draw_handle.get_time(), &mut tile_flash);
+                draw_puzzle(&mut draw_handle, &theme, play.board(), 200, 50, board_sizes);
+                tile_flash.draw(&mut draw_handle, 200, 50, board_sizes);
+                if let Some(previous) = play.previous_board() {
+                    let board = play.board();
+                    draw_last_move(
+                        &mut draw_handle,
+                        &theme,
+                        previous,
+                        board,
+                        200,
+                        50,
+                        board_sizes,
+                    );
+                }
+                draw_handle.draw_text(&play.status(), 290, 172, 10, theme.text);
             } else {
-                draw_puzzle(&mut draw_handle, &initial, 200, 50);
+                draw_puzzle(&mut draw_handle, &theme, &initial, 200, 50, board_sizes);
+
+                let path = solved_tree
+                    .as_mut()
+                    .and_then(|tree| solution_path(&tree.as_map_search_tree()));
+                if let (true, Some(path)) = (show_result, path) {
+                    draw_blank_trace(&mut draw_handle, &theme, &path, 200, 50, board_sizes);
+                }
+            }
+            draw_puzzle_diff(&mut draw_handle, &theme, &initial, &goal, 300, 50);
+
+            let main_board = match (&setting_initial, &play_mode, &auto_play) {
+                (Some(_), _, _) => None,
+                (None, Some(play), _) => Some(*play.board()),
+                (None, None, Some(play)) => Some(*play.board()),
+                (None, None, None) => Some(initial),
+            };
+            if let (true, Some(board)) = (show_tile_distances, main_board) {
+                draw_tile_distances(
+                    &mut draw_handle,
+                    &theme,
+                    &board,
+                    &goal,
+                    200,
+                    50,
+                    board_sizes,
+                );
+            }
+            let distances_label = match show_tile_distances {
+                true => rstr!("h debug: on"),
+                false => rstr!("h debug: off"),
+            };
+            if draw_handle.gui_button(TILE_DISTANCES_BUTTON, Some(distances_label)) {
+                show_tile_distances = !show_tile_distances;
+            }
+
+            if show_result && draw_handle.gui_button(AUTO_PLAY_BUTTON, Some(rstr!("Auto-play"))) {
+                auto_play = solved_tree
+                    .as_mut()
+                    .and_then(|tree| solution_path(&tree.as_map_search_tree()))
+                    .map(|path| {
+                        let interval = AUTO_PLAY_INTERVALS[auto_play_interval_id as usize];
+                        AutoPlay::new(path, interval, draw_handle.get_time())
+                    });
+                play_mode = None;
+            }
+
+            if draw_handle.gui_dropdown_box(
+                AUTO_PLAY_INTERVAL_LIST,
+                Some(rstr!("0.25 s;0.5 s;1 s")),
+                &mut auto_play_interval_id,
+                auto_play_interval_edit,
+            ) {
+                auto_play_interval_edit = !auto_play_interval_edit;
+            }
+
+            if draw_handle.gui_button(SAVE_SEARCH_BUTTON, Some(rstr!("Save search"))) {
+                if let Some(tree) = &solved_tree {
+                    let dump =
+                        SearchDump::new(tree.initial, tree.goal, &tree.map, &tree.expansion_order);
+                    if let Err(e) = dump.save(SEARCH_DUMP_PATH) {
+                        println!("Cannot save search dump: {}", e);
+                    }
+                }
+            }
+
+            if draw_handle.gui_button(EXPORT_TIKZ_BUTTON, Some(rstr!("Export TikZ"))) {
+                let path = solved_tree
+                    .as_mut()
+                    .and_then(|tree| solution_path(&tree.as_map_search_tree()));
+                let root = solution_tree.as_ref().map(|((root, _), _)| root);
+                export_tikz(path, root);
+            }
+
+            if draw_handle.gui_button(REPLAY_SEARCH_BUTTON, Some(rstr!("Replay search"))) {
+                match SearchDump::load(SEARCH_DUMP_PATH) {
+                    Ok(dump) => {
+                        replay = Some(Replay::new(dump));
+                        solve_failure = None;
+                        solve_stats = None;
+                    }
+                    Err(e) => println!("Cannot load search dump: {}", e),
+                }
+            }
+
+            // Stepping back from a live solve replays it from its recorded
+            // expansion order.
+            let steps = STEPS_PER_FRAME[steps_per_frame_id as usize];
+            if draw_handle.gui_button(STEP_BACK_BUTTON, Some(rstr!("< Back"))) {
+                if replay.is_none() {
+                    replay = solved_tree.as_ref().map(|tree| {
+                        let dump = SearchDump::new(
+                            tree.initial,
+                            tree.goal,
+                            &tree.map,
+                            &tree.expansion_order,
+                        );
+                        Replay::paused_at_end(dump)
+                    });
+                }
+                if let Some(replay) = &mut replay {
+                    replay.step_back(steps);
+                    replay_stepped = true;
+                }
+            }
+            if draw_handle.gui_button(STEP_FORWARD_BUTTON, Some(rstr!("Fwd >"))) {
+                if let Some(replay) = &mut replay {
+                    replay.step_forward(steps);
+                    replay_stepped = true;
+                }
+            }
+
+            if let Some(replay) = &replay {
+                draw_handle.draw_text(&replay.status(), 830, 5, 10, theme.text);
+            }
+
+            let compare_label = match report {
+                Some(_) => rstr!("Hide compare"),
+                None => rstr!("Compare"),
+            };
+            if draw_handle.gui_button(COMPARE_BUTTON, Some(compare_label)) {
+                report = match report {
+                    Some(_) => None,
+                    None => Some(Report::start(initial, goal)),
+                };
+            }
+            if let Some(report) = &mut report {
+                report.poll();
+                report.draw(&mut draw_handle, &theme, 1024 - 10 - 360, 240);
+            }
+
+            let parity_label = match parity_explorer {
+                Some(_) => rstr!("Hide classes"),
+                None => rstr!("Classes"),
+            };
+            if draw_handle.gui_button(PARITY_CLASSES_BUTTON, Some(parity_label)) {
+                parity_explorer = match parity_explorer {
+                    Some(_) => None,
+                    None => Some(ParityExplorer::new(&goal)),
+                };
+            }
+            if let Some(explorer) = &mut parity_explorer {
+                let (x, y) = PARITY_PANEL;
+                explorer.draw(&mut draw_handle, &theme, &goal, x, y);
+            }
+
+            // Re-solves the same boards with the next strategy, or flips to
+            // its earlier run. Replays have no strategy to go on from.
+            if show_result
+                && solved_tree.is_some()
+                && replay.is_none()
+                && draw_handle.gui_button(NEXT_STRATEGY_BUTTON, Some(rstr!("Next strategy")))
+            {
+                try_next_strategy = true;
+            }
+            let names = Strategy::all()
+                .into_iter()
+                .filter(|strategy| {
+                    strategy_runs
+                        .get(strategy)
+                        .is_some_and(|run| run.searched(initial, goal))
+                })
+                .map(|strategy| strategy.name())
+                .collect::<Vec<_>>();
+            if show_result && !names.is_empty() {
+                let text = format!("Stacked: {}", names.join(", "));
+                draw_handle.draw_text(&text, 580, 14, 10, theme.text);
+            }
+
+            let pan_label = match session.invert_pan {
+                true => rstr!("Pan: view"),
+                false => rstr!("Pan: tree"),
+            };
+            if draw_handle.gui_button(PAN_BUTTON, Some(pan_label)) {
+                session.invert_pan = !session.invert_pan;
+                if let Err(e) = session.save(SESSION_PATH) {
+                    println!("Cannot save session: {}", e);
+                }
+            }
+
+            let move_list_label = match move_list {
+                Some(_) => rstr!("Hide moves"),
+                None => rstr!("Move list"),
+            };
+            if draw_handle.gui_button(MOVE_LIST_BUTTON, Some(move_list_label)) {
+                move_list = match move_list {
+                    Some(_) => None,
+                    None => Some(MoveList::default()),
+                };
+            }
+            if let (true, Some(list)) = (show_result, &mut move_list) {
+                let (moves, current) = match &auto_play {
+                    Some(play) => (play.moves().to_vec(), play.step().checked_sub(1)),
+                    None => {
+                        let moves = solved_tree
+                            .as_mut()
+                            .and_then(|tree| solution_path(&tree.as_map_search_tree()))
+                            .map(|path| {
+                                path.windows(2)
+                                    .filter_map(|pair| pair[0].direction_to(&pair[1]))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (moves, None)
+                    }
+                };
+                let (x, y) = MOVE_LIST_PANEL;
+                list.draw(&mut draw_handle, &theme, &moves, current, x, y);
+            }
+
+            let play_label = match play_mode {
+                Some(_) => rstr!("Stop"),
+                None => rstr!("Play"),
+            };
+            if draw_handle.gui_button(PLAY_BUTTON, Some(play_label)) {
+                play_mode = match play_mode {
+                    Some(_) => None,
+                    None => Some(PlayMode::new(initial)),
+                };
+                auto_play = None;
+            }
+
+            // Hints show what sliding the tile under the mouse would do.
+            let hints_label = match show_hints {
+                true => rstr!("Hints: on"),
+                false => rstr!("Hints: off"),
+            };
+            if play_mode.is_some() && draw_handle.gui_button(HINTS_BUTTON, Some(hints_label)) {
+                show_hints = !show_hints;
+            }
+
+            // Hands the board played so far to the solver, whose solution
+            // then plays on from there.
+            if play_mode.is_some()
+                && draw_handle.gui_button(SOLVE_PLAY_BUTTON, Some(rstr!("Solve")))
+            {
+                match initial == goal {
+                    true => println!("Already solved"),
+                    false => solve_play = true,
+                }
+            }
+
+            let fringe_label = match show_fringe {
+                true => rstr!("Fringe: on"),
+                false => rstr!("Fringe: off"),
+            };
+            if draw_handle.gui_button(FRINGE_BUTTON, Some(fringe_label)) {
+                show_fringe = !show_fringe;
+            }
+
+            let wavefront_label = match wavefront {
+                true => rstr!("Wavefront: on"),
+                false => rstr!("Wavefront: off"),
+            };
+            if draw_handle.gui_button(WAVEFRONT_BUTTON, Some(wavefront_label)) {
+                wavefront = !wavefront;
+            }
+
+            let ghosts_label = match show_ghosts {
+                true => rstr!("Ghosts: on"),
+                false => rstr!("Ghosts: off"),
+            };
+            if draw_handle.gui_button(GHOSTS_BUTTON, Some(ghosts_label)) {
+                show_ghosts = !show_ghosts;
+            }
+
+            let layout_label = match layout {
+                TreeLayout::Tidy => rstr!("Tidy tree"),
+                TreeLayout::Stable => rstr!("Stable tree"),
+            };
+            if draw_handle.gui_button(LAYOUT_BUTTON, Some(layout_label)) {
+                layout = match layout {
+                    TreeLayout::Tidy => TreeLayout::Stable,
+                    TreeLayout::Stable => TreeLayout::Tidy,
+                };
+            }
+
+            let parity_label = match check_parity {
+                true => rstr!("Parity: check"),
+                false => rstr!("Parity: search"),
+            };
+            if draw_handle.gui_button(PARITY_BUTTON, Some(parity_label)) {
+                check_parity = !check_parity;
+            }
+
+            let g_edges_label = match color_by_g {
+                true => rstr!("Edges: by g"),
+                false => rstr!("Edges: plain"),
+            };
+            if draw_handle.gui_button(G_EDGES_BUTTON, Some(g_edges_label)) {
+                color_by_g = !color_by_g;
+            }
+
+            let f_boards_label = match board_colors {
+                BoardColors::Plain => rstr!("Boards: plain"),
+                BoardColors::F => rstr!("Boards: by f"),
+                BoardColors::Source => rstr!("Boards: terms"),
+            };
+            if draw_handle.gui_button(F_BOARDS_BUTTON, Some(f_boards_label)) {
+                board_colors = match board_colors {
+                    BoardColors::Plain => BoardColors::F,
+                    BoardColors::F if source_values.is_some() => BoardColors::Source,
+                    _ => BoardColors::Plain,
+                };
+            }
+
+            if draw_handle.gui_dropdown_box(
+                RENDER_DEPTH_LIST,
+                Some(rstr!("All depths;Depth 5;Depth 10;Depth 20;Depth 40")),
+                &mut render_depth_id,
+                render_depth_edit,
+            ) {
+                render_depth_edit = !render_depth_edit;
+            }
+
+            let unsolved_label = match keep_unsolved {
+                true => rstr!("Unsolved: show"),
+                false => rstr!("Unsolved: hide"),
+            };
+            if draw_handle.gui_button(UNSOLVED_BUTTON, Some(unsolved_label)) {
+                keep_unsolved = !keep_unsolved;
+            }
+
+            if draw_handle.gui_dropdown_box(
+                MOVE_ORDER_LIST,
+                Some(rstr!("Order: UDLR;Order: RLDU;Order: LRUD;Order: DURL")),
+                &mut move_order_id,
+                move_order_edit,
+            ) {
+                move_order_edit = !move_order_edit;
+            }
+
+            let g_weight_text = CString::new(format!("{:.2}", g_weight)).unwrap();
+            draw_handle.gui_slider_bar(
+                G_WEIGHT_SLIDER,
+                Some(rstr!("g x")),
+                Some(g_weight_text.as_c_str()),
+                &mut g_weight,
+                0.0,
+                MAX_WEIGHT,
+            );
+            let h_weight_text = CString::new(format!("{:.2}", h_weight)).unwrap();
+            draw_handle.gui_slider_bar(
+                H_WEIGHT_SLIDER,
+                Some(rstr!("h x")),
+                Some(h_weight_text.as_c_str()),
+                &mut h_weight,
+                0.0,
+                MAX_WEIGHT,
+            );
+
+            if draw_handle.gui_value_box(
+                ANIM_NODES_BOX,
+                Some(rstr!("Animate up to")),
+                &mut anim_nodes,
+                0,
+                MAX_ANIM_NODES,
+                anim_nodes_edit,
+            ) {
+                anim_nodes_edit = !anim_nodes_edit;
+            }
+            draw_handle.draw_text(
+                "nodes",
+                (ANIM_NODES_BOX.x + ANIM_NODES_BOX.width) as i32 + 5,
+                ANIM_NODES_BOX.y as i32 + 7,
+                10,
+                theme.text,
+            );
+
+            let center_label = match center_on {
+                CenterOn::Goal => rstr!("Center: goal"),
+                CenterOn::Initial => rstr!("Center: initial"),
+            };
+            if draw_handle.gui_button(CENTER_BUTTON, Some(center_label)) {
+                center_on = match center_on {
+                    CenterOn::Goal => CenterOn::Initial,
+                    CenterOn::Initial => CenterOn::Goal,
+                };
+            }
+
+            if let Some((loaded_initial, loaded_goal)) =
+                library_panel.draw(&mut draw_handle, &theme, &initial, &goal)
+            {
+                initial = loaded_initial;
+                goal = loaded_goal;
+                setting_goal = None;
+                setting_initial = None;
+                show_result = false;
+            }
+
+            let share_label = match share_panel {
+                Some(_) => rstr!("Hide"),
+                None => rstr!("Share"),
+            };
+            if draw_handle.gui_button(SHARE_BUTTON, Some(share_label)) {
+                share_panel = match share_panel {
+                    Some(_) => None,
+                    None => Some(SharePanel::default()),
+                };
+            }
+            if let Some(panel) = &mut share_panel {
+                let shared = match (show_result, &replay) {
+                    (true, None) => solved_tree.as_mut().and_then(|tree| {
+                        let path = solution_path(&tree.as_map_search_tree())?;
+                        Some(SharedProblem {
+                            initial: path[0],
+                            goal: *path.last()?,
+                            strategy: Some(solved_strategy),
+                            moves: path
+                                .windows(2)
+                                .filter_map(|pair| pair[0].direction_to(&pair[1]))
+                                .collect(),
+                        })
+                    }),
+                    _ => None,
+                };
+                let (x, y) = SHARE_PANEL;
+                if let Some(loaded) = panel.draw(&mut draw_handle, &theme, shared.as_ref(), x, y) {
+                    initial = loaded.initial;
+                    goal = loaded.goal;
+                    if let Some(strategy) = loaded.strategy {
+                        selected_strategy = strategy_index(strategy) as i32;
+                    }
+                    setting_goal = None;
+                    setting_initial = None;
+                    play_mode = None;
+                    solve_shared = true;
+                }
+            }
+
+            if let Some((node, (x, y))) = hovered {
+                let node = node.borrow();
+                let h = solved_strategy.estimate_h(&node.puzzle, &goal);
+                draw_node_info(&mut draw_handle, &theme, x, y, (node.g, h), &node.parent);
             }
 
             // interactive buttons
@@ -516,55 +2042,406 @@ fn main() {
                 &mut initial,
                 &mut show_result,
                 &mut display_scale,
+                &mut tree_root,
+                &mut theme,
+                &mut random_seed,
             )
         };
 
-        if request_solve {
-            handle.set_target_fps((animate_fps_x5 * 5) as u32);
+        if theme.dark != old_theme_dark {
+            theme.apply_gui_style(&mut handle);
+        }
 
-            let max_nodes = (150 * animate_fps_x5) as usize;
+        let mut rebuild_tree = tree_root != old_tree_root;
 
-            if let Some(mut s) = {
-                match selected_strategy {
-                    1 => solve::<AStarHeuristic1>(initial, goal, &mut handle, &thread, max_nodes),
-                    2 => solve::<AStarHeuristic2>(initial, goal, &mut handle, &thread, max_nodes),
-                    _ => solve::<BfsHeuristic>(initial, goal, &mut handle, &thread, max_nodes),
+        if let Some(replay) = &mut replay {
+            if replay.is_playing() || replay_stepped {
+                if replay.is_playing() {
+                    replay.advance(STEPS_PER_FRAME[steps_per_frame_id as usize]);
                 }
-            } {
-                let count = s.map.len();
-                let s = s.as_map_search_tree();
-                print_map_search_tree(&s);
-                solution_tree = Some((s.into(), count));
-
-                if let Some(((init_node, Some(goal_node)), _)) = &solution_tree {
-                    init_node.build_coord(&PuzzleSizer {
-                        scale: display_scale,
-                    });
-                    offset_xy.0 = -goal_node.borrow().center_x;
+                initial = replay.initial();
+                goal = replay.goal();
+                solved_tree = Some(NativeSearchTree {
+                    goal,
+                    initial,
+                    map: replay.map(),
+                    expansion_order: replay.order(),
+                });
+                show_result = true;
+                rebuild_tree = true;
+            }
+        }
+
+        let mut solve_next_strategy = false;
+        if try_next_strategy {
+            let next = (strategy_index(solved_strategy) + 1) % Strategy::all().len();
+            selected_strategy = next as i32;
+            let next = Strategy::all()[next];
+            match strategy_runs.remove(&next) {
+                Some(run) if run.searched(initial, goal) => {
+                    if let Some(tree) = solved_tree.take() {
+                        let current = StrategyRun {
+                            tree,
+                            stats: solve_stats,
+                            failure: solve_failure.take(),
+                            weights: solved_weights,
+                        };
+                        stack_run(&mut strategy_runs, solved_strategy, current);
+                    }
+                    solved_tree = Some(run.tree);
+                    solve_stats = run.stats;
+                    solve_failure = run.failure;
+                    solved_weights = run.weights;
+                    solved_strategy = next;
+                    auto_play = None;
+                    rebuild_tree = true;
+                    display_scale = STRATEGY_SCALES[strategy_index(next)];
                 }
+                _ => solve_next_strategy = true,
+            }
+        }
+
+        if request_solve
+            || solve_play
+            || solve_next_strategy
+            || solve_shared
+            || demo_round.is_some()
+        {
+            // Demo rounds are always animated.
+            let fps_x5 = match demo_round {
+                Some(_) => animate_fps_x5.max(DEMO_FPS_X5),
+                None => animate_fps_x5,
+            };
+            handle.set_target_fps((fps_x5 * 5) as u32);
+
+            let settings = AnimationSettings {
+                max_nodes: match fps_x5 {
+                    0 => 0,
+                    _ => anim_nodes as usize,
+                },
+                steps_per_frame: STEPS_PER_FRAME[steps_per_frame_id as usize],
+                show_fringe,
+                wavefront,
+                layout,
+                check_parity,
+                keep_unsolved,
+                move_order: MOVE_ORDERS[move_order_id as usize],
+                weights: CostWeights::new(g_weight as f64, h_weight as f64),
+                theme,
+                duration: animate_for,
+            };
+            // The run on screen stays around to flip back to.
+            if let (Some(tree), None) = (solved_tree.take(), &replay) {
+                let current = StrategyRun {
+                    tree,
+                    stats: solve_stats,
+                    failure: solve_failure.take(),
+                    weights: solved_weights,
+                };
+                stack_run(&mut strategy_runs, solved_strategy, current);
+            }
+            solved_strategy = Strategy::all()[selected_strategy as usize];
+            solved_weights = settings.weights;
+            replay = None;
+
+            let outcome = match solved_strategy {
+                Strategy::Bfs => solve::<BfsHeuristic>(
+                    solved_strategy,
+                    initial,
+                    goal,
+                    &mut handle,
+                    &thread,
+                    settings,
+                    &follow_search,
+                ),
+                Strategy::AStar1 => solve::<AStarHeuristic1>(
+                    solved_strategy,
+                    initial,
+                    goal,
+                    &mut handle,
+                    &thread,
+                    settings,
+                    &follow_search,
+                ),
+                Strategy::AStar2 => solve::<AStarHeuristic2>(
+                    solved_strategy,
+                    initial,
+                    goal,
+                    &mut handle,
+                    &thread,
+                    settings,
+                    &follow_search,
+                ),
+                Strategy::Manhattan => solve::<ManhattanHeuristic>(
+                    solved_strategy,
+                    initial,
+                    goal,
+                    &mut handle,
+                    &thread,
+                    settings,
+                    &follow_search,
+                ),
+                Strategy::Max => solve::<ManhattanOrMisplaced>(
+                    solved_strategy,
+                    initial,
+                    goal,
+                    &mut handle,
+                    &thread,
+                    settings,
+                    &follow_search,
+                ),
+            };
+            solve_failure = outcome.failure_message();
+            solve_stats = outcome.stats();
+            if let Some((mut s, expansion_order)) = outcome.into_search() {
+                print_map_search_tree(&s.as_map_search_tree());
+                solved_tree = Some(NativeSearchTree {
+                    goal: s.goal,
+                    initial: s.initial,
+                    map: std::mem::take(&mut s.map),
+                    expansion_order,
+                });
+                rebuild_tree = true;
+                // Zooming by hand holds until the next solve.
+                display_scale = STRATEGY_SCALES[selected_strategy as usize];
             } else {
+                solved_tree = None;
                 solution_tree = None;
             }
             handle.set_target_fps(60);
 
             show_result = true;
+
+            if demo_round.is_some() || solve_play {
+                play_mode = None;
+                auto_play = solved_tree
+                    .as_mut()
+                    .and_then(|tree| solution_path(&tree.as_map_search_tree()))
+                    .map(|path| {
+                        let interval = AUTO_PLAY_INTERVALS[auto_play_interval_id as usize];
+                        AutoPlay::new(path, interval, handle.get_time())
+                    });
+            }
         }
+
+        let recenter = rebuild_tree || center_on != old_center_on;
+
+        if rebuild_tree {
+            if let Some(tree) = &mut solved_tree {
+                let count = tree.map.len();
+                tree_max_g = tree.map.values().map(|&(_, g)| g).max().unwrap_or(0);
+                let tree_goal = tree.goal;
+                f_values = Some(FValues::new(&tree.map, |puzzle| {
+                    solved_strategy.estimate_h(puzzle, &tree_goal)
+                }));
+                source_values = solved_strategy.sources().map(|names| {
+                    HeuristicSources::new(&tree.map, names, |puzzle| {
+                        solved_strategy
+                            .estimate_source(puzzle, &tree_goal)
+                            .unwrap_or(0)
+                    })
+                });
+                if source_values.is_none() && board_colors == BoardColors::Source {
+                    board_colors = BoardColors::Plain;
+                }
+                solution_tree = Some((build_draw_tree(tree, tree_root), count));
+            }
+        }
+
+        // Keep the selection on the same board across rebuilds.
+        if rebuild_tree || solution_tree.is_none() {
+            selected_node = match (&selected_node, &solution_tree) {
+                (Some(node), Some(((root, _), _))) => root.find(&node.borrow().puzzle),
+                _ => None,
+            };
+        }
+
+        if recenter {
+            if let Some(((root_node, path_end_node), _)) = &solution_tree {
+                root_node.build_coord(&PuzzleSizer {
+                    scale: display_scale,
+                });
+                // The tree hangs from one end of the solution path and
+                // `path_end_node` is the other one. Without a solution there
+                // is only the root to center on.
+                offset_xy.0 = match (path_end_node, tree_root, center_on) {
+                    (None, _, _) => -root_node.borrow().center_x,
+                    (Some(path_end_node), TreeRoot::Initial, CenterOn::Goal)
+                    | (Some(path_end_node), TreeRoot::Goal, CenterOn::Initial) => {
+                        -path_end_node.borrow().center_x
+                    }
+                    (Some(_), _, _) => -root_node.borrow().center_x,
+                };
+            }
+        }
+    }
+}
+
+/// Pans the view so `node` is on screen, centering it on the axes it was
+/// off screen along.
+fn scroll_into_view(node: &RcRefDrawTreeNode, sizer: &PuzzleSizer, offset_xy: &mut (i32, i32)) {
+    let node = node.borrow();
+    let margin = sizer.puzzle_center_offset() + 10;
+    let depth_y = sizer.depth_y(node.depth);
+    let x = TREE_ORIGIN.0 + offset_xy.0 + node.center_x;
+    let y = TREE_ORIGIN.1 + offset_xy.1 + depth_y;
+
+    if x - margin < MAIN_BOUND.left || x + margin > MAIN_BOUND.right {
+        offset_xy.0 = -node.center_x;
+    }
+    if y - margin < MAIN_BOUND.top || y + margin > 768 {
+        offset_xy.1 = (MAIN_BOUND.top + 768) / 2 - TREE_ORIGIN.1 - depth_y;
+    }
+}
+
+/// Headless `--bench <path>`: every strategy on the boards of the default
+/// seeds, written to `path` as CSV.
+fn run_bench(path: &str) {
+    let rows = bench::run(bench::DEFAULT_SEEDS, GoalPreset::Spiral.puzzle());
+    let written = std::fs::File::create(path).and_then(|file| bench::write_csv(&rows, file));
+    match written {
+        Ok(()) => println!("Benchmark of {} runs written to {}", rows.len(), path),
+        Err(e) => println!("Cannot write benchmark to {}: {}", path, e),
+    }
+}
+
+/// Headless `--stats`: `initial` solved with the strategy named `strategy`,
+/// its `StatsReport` written to stdout. Fails with exit code 1 when there is
+/// no such strategy or no solution, so scripts notice.
+fn run_stats(initial: Puzzle, strategy: &str, deterministic: bool) {
+    let config = SolveConfig::new(strategy_named(strategy));
+    let Some(solution) = nine::logic::solve(initial, GoalPreset::Spiral.puzzle(), &config) else {
+        eprintln!("No solution from {:?}", initial);
+        std::process::exit(1);
+    };
+    let report = bench::StatsReport::new(&solution, deterministic);
+    if let Err(e) = report.write_json(std::io::stdout().lock()) {
+        eprintln!("Cannot write stats: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Headless `--count`: the solution length of `initial` with the strategy
+/// named `strategy` and the nodes expanded to find it, as `solve_count`
+/// finds them. Fails like `run_stats`.
+fn run_count(initial: Puzzle, strategy: &str) {
+    let config = SolveConfig::new(strategy_named(strategy));
+    let Some(count) = nine::logic::solve_count(initial, GoalPreset::Spiral.puzzle(), &config)
+    else {
+        eprintln!("No solution from {:?}", initial);
+        std::process::exit(1);
+    };
+    println!(
+        "{}, {} nodes expanded",
+        solution_len_text(count.steps),
+        count.stats.expanded
+    );
+}
+
+/// The strategy called `name`, in any case. Exits with code 1 when there is
+/// none.
+fn strategy_named(name: &str) -> Strategy {
+    match Strategy::all()
+        .into_iter()
+        .find(|known| known.name().eq_ignore_ascii_case(name))
+    {
+        Some(strategy) => strategy,
+        None => {
+            let names = Strategy::all().map(|known| known.name()).join(", ");
+            eprintln!("Unknown strategy \"{}\", expected one of {}", name, names);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Writes the solution row to `TIKZ_PATH_FILE` and the drawn tree to
+/// `TIKZ_TREE_FILE`, whichever of them exists.
+fn export_tikz(path: Option<Vec<Puzzle>>, root: Option<&RcRefDrawTreeNode>) {
+    if let Some(path) = path {
+        let written = File::create(TIKZ_PATH_FILE)
+            .and_then(|file| tikz::write_path(&path, &TIKZ_SIZER, BufWriter::new(file)));
+        match written {
+            Ok(()) => println!("Solution written to {}", TIKZ_PATH_FILE),
+            Err(e) => println!("Cannot write {}: {}", TIKZ_PATH_FILE, e),
+        }
+    }
+
+    if let Some(root) = root {
+        let written = File::create(TIKZ_TREE_FILE)
+            .and_then(|file| tikz::write_tree(root, &TIKZ_SIZER, BufWriter::new(file)));
+        match written {
+            Ok(()) => println!("Search tree written to {}", TIKZ_TREE_FILE),
+            Err(e) => println!("Cannot write {}: {}", TIKZ_TREE_FILE, e),
+        }
+    }
+}
+
+fn describe_strategy(strategy: Strategy) -> String {
+    let yes_no = |value| if value { "yes" } else { "no" };
+    format!(
+        "{}: admissible {}, consistent {}",
+        strategy.name(),
+        yes_no(strategy.is_admissible()),
+        yes_no(strategy.is_consistent())
+    )
+}
+
+/// Position of `strategy` in `STRATEGY_LIST`, and in `STRATEGY_SCALES`.
+fn strategy_index(strategy: Strategy) -> usize {
+    Strategy::all()
+        .iter()
+        .position(|known| *known == strategy)
+        .unwrap_or(0)
+}
+
+/// Solution length against the longest any board of this size needs, e.g.
+/// "22/31 moves".
+fn solution_len_text(len: usize) -> String {
+    match gods_number(Puzzle::SIDE) {
+        Some(max) => format!("{}/{} moves", len, max),
+        None => format!("{} moves", len),
     }
 }
 
+fn build_draw_tree(
+    tree: &mut NativeSearchTree,
+    root: TreeRoot,
+) -> (RcRefDrawTreeNode, Option<RcRefDrawTreeNode>) {
+    let tree = tree.as_map_search_tree();
+    // Re-rooting walks up from the goal, which a parent loop never lets end.
+    let root = match check_parent_links(&tree) {
+        true => root,
+        false => TreeRoot::Initial,
+    };
+    RcRefDrawTreeNode::new_rooted_from_map_search_tree(&tree, tree.goal(), root)
+}
+
 fn button_draw(
     setting_goal: &mut Option<SetPuzzle>,
     setting_initial: &mut Option<SetPuzzle>,
     mut draw_handle: RaylibDrawHandle<'_>,
     initial: &mut Puzzle,
     show_result: &mut bool,
-    display_scale: &mut i32,
+    display_scale: &mut f32,
+    tree_root: &mut TreeRoot,
+    theme: &mut Theme,
+    random_seed: &mut Option<(u64, Puzzle)>,
 ) -> bool {
     if draw_handle.gui_button(RANDOM_INIT_BUTTON, Some(rstr!("Random init"))) {
-        *initial = Puzzle::from_random();
+        let seed = rand::thread_rng().gen::<u64>();
+        *initial = Puzzle::from_rng(&mut StdRng::seed_from_u64(seed));
+        *random_seed = Some((seed, *initial));
+        println!("Random initial board from seed {}", seed);
         *show_result = false;
     }
 
+    if let Some((seed, puzzle)) = random_seed {
+        if puzzle == initial {
+            let text = format!("seed {}", seed);
+            draw_handle.draw_text(&text, 200, 36, 10, theme.text);
+        }
+    }
+
     if draw_handle.gui_button(SET_GOAL_BUTTON, Some(rstr!("Set goal"))) {
         *setting_goal = Some(SetPuzzle::new());
         *show_result = false;
@@ -576,11 +2453,30 @@ fn button_draw(
     }
 
     if draw_handle.gui_button(PLUS_BUTTON, Some(rstr!("+"))) {
-        *display_scale += 1;
+        *display_scale += 1.0;
     }
 
-    if *display_scale > 1 && draw_handle.gui_button(MINUS_BUTTON, Some(rstr!("-"))) {
-        *display_scale -= 1;
+    if *display_scale > MIN_SCALE && draw_handle.gui_button(MINUS_BUTTON, Some(rstr!("-"))) {
+        *display_scale = (*display_scale - 1.0).max(MIN_SCALE);
+    }
+
+    let theme_label = match theme.dark {
+        true => rstr!("Light theme"),
+        false => rstr!("Dark theme"),
+    };
+    if draw_handle.gui_button(THEME_BUTTON, Some(theme_label)) {
+        *theme = theme.toggled();
+    }
+
+    let root_label = match tree_root {
+        TreeRoot::Initial => rstr!("Root: initial"),
+        TreeRoot::Goal => rstr!("Root: goal"),
+    };
+    if draw_handle.gui_button(ROOT_BUTTON, Some(root_label)) {
+        *tree_root = match tree_root {
+            TreeRoot::Initial => TreeRoot::Goal,
+            TreeRoot::Goal => TreeRoot::Initial,
+        };
     }
 
     setting_goal.is_none()
@@ -588,14 +2484,109 @@ fn button_draw(
         && draw_handle.gui_button(SOLVE_BUTTON, Some(rstr!("Solve")))
 }
 
+/// A search tree along with the order its boards were expanded in.
+type KeptSearch<'a> = (AnimatedSearchTree<'a>, Vec<Puzzle>);
+
+/// How a solve started from the UI ended. Searches that did not reach the
+/// goal keep their tree in `kept` when `keep_unsolved` is set.
+enum SolveOutcome<'a> {
+    Solved(KeptSearch<'a>, SearchStats),
+    /// The goal cannot be reached: the parity check ruled it out before
+    /// searching, or every board the initial one leads to was explored.
+    Exhausted {
+        by_parity: bool,
+        expanded: usize,
+        kept: Option<KeptSearch<'a>>,
+    },
+    /// Stopped from the animation before the goal was reached.
+    Aborted {
+        expanded: usize,
+        kept: Option<KeptSearch<'a>>,
+    },
+}
+
+impl<'a> SolveOutcome<'a> {
+    /// What the result line says when the goal was not reached.
+    fn failure_message(&self) -> Option<String> {
+        match self {
+            SolveOutcome::Solved(..) => None,
+            SolveOutcome::Exhausted {
+                by_parity: true, ..
+            } => Some("No solution, wrong parity for the goal".to_string()),
+            SolveOutcome::Exhausted { expanded, .. } => {
+                Some(format!("No solution, all {} nodes explored", expanded))
+            }
+            SolveOutcome::Aborted { expanded, .. } => {
+                Some(format!("Stopped after {} nodes, not solved", expanded))
+            }
+        }
+    }
+
+    fn stats(&self) -> Option<SearchStats> {
+        match self {
+            SolveOutcome::Solved(_, stats) => Some(*stats),
+            _ => None,
+        }
+    }
+
+    fn into_search(self) -> Option<KeptSearch<'a>> {
+        match self {
+            SolveOutcome::Solved(search, _) => Some(search),
+            SolveOutcome::Exhausted { kept, .. } | SolveOutcome::Aborted { kept, .. } => kept,
+        }
+    }
+}
+
+/// Expansions the animated search with `strategy` will make, by running the
+/// same search with `solve_count` first. Without a solution it is estimated
+/// as every board of the parity of `initial`, which the exhaustive search
+/// explores.
+fn expansions_to_animate(
+    strategy: Strategy,
+    initial: Puzzle,
+    goal: Puzzle,
+    settings: &AnimationSettings,
+) -> usize {
+    let config = SolveConfig::new(strategy)
+        .order(settings.move_order)
+        .weights(settings.weights);
+    match nine::logic::solve_count(initial, goal, &config) {
+        Some(count) => count.stats.expanded,
+        None => (1..=Puzzle::SIDE * Puzzle::SIDE).product::<usize>() / 2,
+    }
+}
+
+/// Animated search with `T`, the heuristic of `strategy`.
 fn solve<'a, T: Heuristic>(
+    strategy: Strategy,
     initial: Puzzle,
     goal: Puzzle,
     handle: &'a mut RaylibHandle,
     thread: &'a RaylibThread,
-    max_nodes: usize,
-) -> Option<AnimatedSearchTree<'a>> {
-    let alice = load_alice(handle, &thread);
+    settings: AnimationSettings,
+    follow: &'a Cell<bool>,
+) -> SolveOutcome<'a> {
+    let start = Instant::now();
+    let (alice, max_nodes) = match load_alice(handle, thread) {
+        Ok(alice) => (alice, settings.max_nodes),
+        Err(e) => {
+            println!("Cannot load animation, solving without it: {}", e);
+            (Vec::new(), 0)
+        }
+    };
+    // A paced animation draws the whole search, however large.
+    let pacing = match (settings.duration, max_nodes) {
+        (Some(duration), 1..) => Some(Pacing {
+            total: expansions_to_animate(strategy, initial, goal, &settings),
+            duration,
+            start: handle.get_time(),
+        }),
+        _ => None,
+    };
+    let max_nodes = match pacing {
+        Some(_) => usize::MAX,
+        None => max_nodes,
+    };
     let mut tree = OwnedMapSearchTree {
         inner: AnimatedSearchTree {
             goal,
@@ -604,16 +2595,176 @@ fn solve<'a, T: Heuristic>(
             handle,
             thread,
             max_nodes: Cell::new(max_nodes),
+            cancelled: Cell::new(false),
+            steps_per_frame: settings.steps_per_frame,
+            pending_steps: 0,
+            pacing,
+            follow,
+            // A stable layout only grows to the right of the root.
+            view_offset: match settings.layout {
+                TreeLayout::Tidy => (1024 / 2, ANIM_BOUND.top + 20),
+                TreeLayout::Stable => (ANIM_BOUND.left + 20, ANIM_BOUND.top + 20),
+            },
+            drag_from: None,
+            show_fringe: settings.show_fringe,
+            wavefront: settings.wavefront,
             alice,
+            theme: settings.theme,
+            draw_tree: IncrementalDrawTree::new(initial, settings.layout),
+            progress: SearchProgress::default(),
+            max_cost: match T::new().is_admissible() && settings.weights == CostWeights::default() {
+                true => gods_number(Puzzle::SIDE).map(|moves| moves as f64),
+                false => None,
+            },
         },
     };
     let mut tree_ref: MapSearchTree<'_, AnimatedSearchTree<'_>> = tree.make_ref();
-    solve_from_initial::<_, T>(initial, goal, &mut tree_ref);
-    match tree_ref.goal_reached() {
-        true => {
-            print_map_search_tree(&tree_ref);
-            Some(tree.inner)
+    println!("Solving with {}", T::new().name());
+    let mut recorder = RecordExpansions::new(&mut tree_ref);
+    let search = match settings.check_parity {
+        true => solve_from_initials_bounded::<_, T>,
+        false => solve_from_initials_exhaustive::<_, T>,
+    };
+    search(
+        &[initial],
+        goal,
+        &mut recorder,
+        settings.move_order,
+        settings.weights,
+        None,
+    );
+    let expansion_order = recorder.into_order();
+    let solved = tree_ref.goal_reached();
+    if solved {
+        print_map_search_tree(&tree_ref);
+        // The path is rebuilt from the parent links the animated tree kept,
+        // which the solver's own check never sees.
+        if cfg!(debug_assertions) {
+            if let Some(Err(index)) = solution_path(&tree_ref).map(|path| verify_path(&path)) {
+                println!("Illegal move from board {} of the solution path", index);
+            }
         }
-        false => None,
+    }
+
+    let progress = tree.inner.progress;
+    let expanded = progress.expanded;
+    let cancelled = tree.inner.cancelled.get();
+    let search = (tree.inner, expansion_order);
+    if solved {
+        let stats = SearchStats {
+            expanded,
+            generated: progress.generated,
+            max_open: progress.max_open,
+            elapsed: start.elapsed(),
+        };
+        println!("Solved in {:?}, {} nodes expanded", stats.elapsed, expanded);
+        return SolveOutcome::Solved(search, stats);
+    }
+
+    let kept = settings.keep_unsolved.then_some(search);
+    match cancelled {
+        true => SolveOutcome::Aborted { expanded, kept },
+        false => SolveOutcome::Exhausted {
+            // The parity check fails a board before expanding anything.
+            by_parity: settings.check_parity && !initial.is_solvable_to(&goal),
+            expanded,
+            kept,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two moves from the initial board to the goal, all three boards closed
+    /// in path order.
+    fn three_board_tree() -> NativeSearchTree {
+        let goal = GoalPreset::Spiral.puzzle();
+        let middle = goal.move_zero(Direction::Up).unwrap();
+        let initial = middle.move_zero(Direction::Left).unwrap();
+        NativeSearchTree {
+            goal,
+            initial,
+            map: HashMap::from([
+                (initial, (initial, 0)),
+                (middle, (initial, 1)),
+                (goal, (middle, 2)),
+            ]),
+            expansion_order: vec![initial, middle, goal],
+        }
+    }
+
+    #[test]
+    fn path_pairs_follow_the_solution() {
+        let mut tree = three_board_tree();
+        let (initial, middle, goal) = (tree.initial, tree.expansion_order[1], tree.goal);
+
+        let pairs = tree
+            .as_map_search_tree()
+            .iter_path_pairs()
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(initial, middle), (middle, goal)]);
+    }
+
+    #[test]
+    fn search_tree_report_is_written_to_the_sink() {
+        let mut tree = three_board_tree();
+        let (initial, middle, goal) = (tree.initial, tree.expansion_order[1], tree.goal);
+
+        let mut out = Vec::new();
+        let path = write_map_search_tree(&tree.as_map_search_tree(), &mut out).unwrap();
+        assert_eq!(path, Some(vec![initial, middle, goal]));
+        let moves = [initial.direction_to(&middle), middle.direction_to(&goal)]
+            .map(|direction| direction.unwrap().to_string())
+            .join(" ");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{}\n{}\n{}\nMoves: {}\nTotal nodes: 3\n",
+                initial, middle, goal, moves
+            )
+        );
+
+        // A loop is all that gets written.
+        tree.map.insert(initial, (middle, 0));
+        let mut out = Vec::new();
+        let path = write_map_search_tree(&tree.as_map_search_tree(), &mut out).unwrap();
+        assert_eq!(path, None);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("Parent links loop through 2 boards:\n"));
+    }
+
+    #[test]
+    fn pacing_spreads_the_expansions_over_the_duration() {
+        let pacing = Pacing {
+            total: 1000,
+            duration: 10.0,
+            start: 5.0,
+        };
+        assert_eq!(pacing.due(5.0), 0);
+        assert_eq!(pacing.due(7.5), 250);
+        assert_eq!(pacing.due(15.0), 1000);
+        assert_eq!(pacing.due(60.0), 1000);
+    }
+
+    #[test]
+    fn follow_mode_glides_onto_the_target() {
+        assert_eq!(follow_offset((0, 100), (400, 100)), (100, 100));
+        assert_eq!(follow_offset((398, -2), (400, 0)), (400, 0));
+
+        let mut offset = (0, 0);
+        for _ in 0..30 {
+            offset = follow_offset(offset, (-500, 300));
+        }
+        assert_eq!(offset, (-500, 300));
+    }
+
+    #[test]
+    fn missing_alice_frames_are_skipped() {
+        assert_eq!(alice_frame(0, 0), None);
+        assert_eq!(alice_frame(0, 17), None);
+        assert_eq!(alice_frame(5, 17), Some(2));
     }
 }
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::logic::Puzzle;
+
+pub const SEARCH_DUMP_PATH: &str = "search.json";
+
+/// Bumped whenever the dump layout changes, older dumps are rejected.
+pub const SEARCH_DUMP_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DumpEntry {
+    pub puzzle: Puzzle,
+    pub parent: Puzzle,
+    pub g: i32,
+}
+
+/// A finished search, closed boards listed in the order they were expanded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchDump {
+    pub version: u32,
+    pub initial: Puzzle,
+    pub goal: Puzzle,
+    pub expansions: Vec<DumpEntry>,
+}
+
+#[derive(Deserialize)]
+struct DumpHeader {
+    version: u32,
+}
+
+impl SearchDump {
+    /// Pairs every board of `order` with its parent and g from `map`. Boards
+    /// missing from `map` are skipped.
+    pub fn new(
+        initial: Puzzle,
+        goal: Puzzle,
+        map: &HashMap<Puzzle, (Puzzle, i32)>,
+        order: &[Puzzle],
+    ) -> Self {
+        let expansions = order
+            .iter()
+            .filter_map(|puzzle| {
+                map.get(puzzle).map(|&(parent, g)| DumpEntry {
+                    puzzle: *puzzle,
+                    parent,
+                    g,
+                })
+            })
+            .collect();
+
+        SearchDump {
+            version: SEARCH_DUMP_VERSION,
+            initial,
+            goal,
+            expansions,
+        }
+    }
+
+    /// Reads the dump at `path`. A dump written by another version of the
+    /// format is an `InvalidData` error.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        let header: DumpHeader = serde_json::from_str(&content)?;
+        if header.version != SEARCH_DUMP_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "search dump version {} is not supported, expected {}",
+                    header.version, SEARCH_DUMP_VERSION
+                ),
+            ));
+        }
+
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// The closed set after the first `count` expansions.
+    pub fn map_after(&self, count: usize) -> HashMap<Puzzle, (Puzzle, i32)> {
+        self.expansions
+            .iter()
+            .take(count)
+            .map(|entry| (entry.puzzle, (entry.parent, entry.g)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::logic::Direction;
+
+    /// A file of its own under the temporary directory, so tests running at
+    /// once do not write over each other.
+    fn dump_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nine-{}-{}.json", std::process::id(), name))
+    }
+
+    fn three_move_dump() -> SearchDump {
+        let initial = Puzzle::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]);
+        let mut map = HashMap::from([(initial, (initial, 0))]);
+        let mut order = vec![initial];
+        for direction in [Direction::Up, Direction::Left, Direction::Down] {
+            let parent = *order.last().unwrap();
+            let child = parent.move_zero(direction).unwrap();
+            map.insert(child, (parent, map[&parent].1 + 1));
+            order.push(child);
+        }
+        SearchDump::new(initial, *order.last().unwrap(), &map, &order)
+    }
+
+    #[test]
+    fn saved_dump_loads_back_expansion_by_expansion() {
+        let path = dump_path("round-trip");
+        let dump = three_move_dump();
+        dump.save(&path).unwrap();
+        let loaded = SearchDump::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!((loaded.initial, loaded.goal), (dump.initial, dump.goal));
+        for count in 0..=dump.expansions.len() {
+            assert_eq!(loaded.map_after(count), dump.map_after(count), "{}", count);
+            assert_eq!(loaded.map_after(count).len(), count);
+        }
+    }
+
+    #[test]
+    fn other_version_is_invalid_data() {
+        let path = dump_path("version");
+        let mut dump = three_move_dump();
+        dump.version = 2;
+        dump.save(&path).unwrap();
+        let loaded = SearchDump::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        // A dump that does not parse is `InvalidData` too, the message tells
+        // the version check apart.
+        let error = loaded.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("version 2"), "{}", error);
+    }
+}
@@ -0,0 +1,29 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+pub const SESSION_PATH: &str = "session.json";
+
+/// Preferences kept in a JSON file between sessions. Fields missing from the
+/// file keep their default, so files written by older versions still load.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Dragging moves the view over the tree instead of the tree itself.
+    pub invert_pan: bool,
+}
+
+impl Session {
+    /// Reads the session at `path`, a missing file is the default session.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Session::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,140 @@
+use std::io;
+
+use nine::logic::Puzzle;
+
+use crate::draw_tree::{PuzzleSizer, RcRefDrawTreeNode};
+
+pub const TIKZ_PATH_FILE: &str = "solution.tex";
+pub const TIKZ_TREE_FILE: &str = "search-tree.tex";
+
+/// Empty cells between two boards of a solution row.
+const ROW_GAP: i32 = 2;
+
+fn begin(out: &mut impl io::Write, sizer: &PuzzleSizer) -> io::Result<()> {
+    // One layout pixel per point, y growing downwards like on screen.
+    writeln!(out, "\\begin{{tikzpicture}}[x=1pt, y=-1pt,")?;
+    writeln!(
+        out,
+        "    tile/.style={{draw, fill=white, minimum size={}pt, inner sep=0pt, font=\\tiny}},",
+        sizer.puzzle_cell()
+    )?;
+    writeln!(out, "    edge/.style={{draw=gray}},")?;
+    writeln!(out, "    path edge/.style={{draw=black, very thick}}]")
+}
+
+fn end(out: &mut impl io::Write) -> io::Result<()> {
+    writeln!(out, "\\end{{tikzpicture}}")?;
+    out.flush()
+}
+
+/// One node per tile of `puzzle` around `(x, y)`, the blank left out.
+fn write_board(
+    out: &mut impl io::Write,
+    sizer: &PuzzleSizer,
+    puzzle: &Puzzle,
+    (x, y): (i32, i32),
+) -> io::Result<()> {
+    let cell = sizer.puzzle_cell();
+    for i in 0..3 {
+        for j in 0..3 {
+            let value = puzzle.get_value(i, j);
+            if value != 0 {
+                let (tile_x, tile_y) = (x + (j as i32 - 1) * cell, y + (i as i32 - 1) * cell);
+                writeln!(
+                    out,
+                    "  \\node[tile] at ({}, {}) {{{}}};",
+                    tile_x, tile_y, value
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The boards of `path` in a row, left to right, joined by bold lines.
+pub fn write_path(path: &[Puzzle], sizer: &PuzzleSizer, mut out: impl io::Write) -> io::Result<()> {
+    let half = sizer.puzzle_center_offset();
+    let step = (3 + ROW_GAP) * sizer.puzzle_cell();
+
+    begin(&mut out, sizer)?;
+    for (i, puzzle) in path.iter().enumerate() {
+        let x = i as i32 * step;
+        if i > 0 {
+            writeln!(
+                out,
+                "  \\draw[path edge] ({}, 0) -- ({}, 0);",
+                x - step + half,
+                x - half
+            )?;
+        }
+        write_board(&mut out, sizer, puzzle, (x, 0))?;
+    }
+    end(&mut out)
+}
+
+fn write_edges(
+    out: &mut impl io::Write,
+    sizer: &PuzzleSizer,
+    node: &RcRefDrawTreeNode,
+) -> io::Result<()> {
+    let node = node.borrow();
+    let half = sizer.puzzle_center_offset();
+    let bottom = sizer.depth_y(node.depth) + half;
+
+    for child in node.children() {
+        let child_ref = child.borrow();
+        let top = sizer.depth_y(child_ref.depth) - half;
+        let style = match node.on_path() && child_ref.on_path() {
+            true => "path edge",
+            false => "edge",
+        };
+        // Down from the parent, across and down into the child, as drawn
+        // on screen.
+        writeln!(
+            out,
+            "  \\draw[{}] ({}, {}) -- ({}, {}) -| ({}, {});",
+            style,
+            node.center_x,
+            bottom,
+            node.center_x,
+            (bottom + top) / 2,
+            child_ref.center_x,
+            top
+        )?;
+        drop(child_ref);
+
+        write_edges(out, sizer, child)?;
+    }
+    Ok(())
+}
+
+fn write_boards(
+    out: &mut impl io::Write,
+    sizer: &PuzzleSizer,
+    node: &RcRefDrawTreeNode,
+) -> io::Result<()> {
+    let node = node.borrow();
+    let center = (node.center_x, sizer.depth_y(node.depth));
+    write_board(out, sizer, &node.puzzle, center)?;
+
+    for child in node.children() {
+        write_boards(out, sizer, child)?;
+    }
+    Ok(())
+}
+
+/// The whole tree under `root`, laid out as `draw_tree` lays it out for
+/// `sizer`. Edges on the solution path are bold.
+pub fn write_tree(
+    root: &RcRefDrawTreeNode,
+    sizer: &PuzzleSizer,
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    root.build_coord(sizer);
+
+    begin(&mut out, sizer)?;
+    // Edges first, so the tiles are drawn over their ends.
+    write_edges(&mut out, sizer, root)?;
+    write_boards(&mut out, sizer, root)?;
+    end(&mut out)
+}
@@ -0,0 +1,93 @@
+use nine::logic::{Direction, Puzzle};
+
+use super::tile_move::{notify_tile_move, TileMoveListener};
+
+/// Replays a solution on the main board, one blank move per interval.
+pub struct AutoPlay {
+    path: Vec<Puzzle>,
+    moves: Vec<Direction>,
+    board: Puzzle,
+    step: usize,
+    interval: f64,
+    next_step_at: f64,
+    inconsistent_at: Option<usize>,
+}
+
+impl AutoPlay {
+    pub fn new(path: Vec<Puzzle>, interval: f64, now: f64) -> Self {
+        let moves = path
+            .windows(2)
+            .map_while(|pair| pair[0].direction_to(&pair[1]))
+            .collect();
+
+        AutoPlay {
+            board: path[0],
+            path,
+            moves,
+            step: 0,
+            interval,
+            next_step_at: now + interval,
+            inconsistent_at: None,
+        }
+    }
+
+    pub fn board(&self) -> &Puzzle {
+        &self.board
+    }
+
+    /// The blank moves of the path, in order.
+    pub fn moves(&self) -> &[Direction] {
+        &self.moves
+    }
+
+    /// Moves made so far.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The board the last move was made from, none before the first one.
+    pub fn previous_board(&self) -> Option<&Puzzle> {
+        self.step.checked_sub(1).map(|step| &self.path[step])
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step + 1 >= self.path.len()
+    }
+
+    /// Applies the next move once its time has come, telling `listener`
+    /// which tile it slid. A move that does not lead to the next board of
+    /// the path stops the playback.
+    pub fn update(&mut self, now: f64, listener: &mut impl TileMoveListener) {
+        if self.is_finished() || self.inconsistent_at.is_some() || now < self.next_step_at {
+            return;
+        }
+
+        let next = self
+            .moves
+            .get(self.step)
+            .and_then(|&direction| self.board.move_zero(direction));
+
+        match next {
+            Some(next) if next == self.path[self.step + 1] => {
+                notify_tile_move(listener, &self.board, &next);
+                self.board = next;
+                self.step += 1;
+                self.next_step_at = now + self.interval;
+            }
+            _ => {
+                println!(
+                    "Auto-play stopped, inconsistent move at step {}",
+                    self.step + 1
+                );
+                self.inconsistent_at = Some(self.step + 1);
+            }
+        }
+    }
+
+    pub fn status(&self) -> String {
+        match self.inconsistent_at {
+            Some(step) => format!("Inconsistent at step {}", step),
+            None => format!("Step {}/{}", self.step, self.path.len() - 1),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use nine::{
+    bench::board_from_seed,
+    logic::{Puzzle, Strategy},
+};
+use rand::Rng;
+use raylib::prelude::*;
+
+/// Idle seconds before the demo starts when `--demo` is given no value.
+pub const DEFAULT_IDLE_TIMEOUT: f64 = 60.0;
+
+/// Seconds a finished round stays on screen before the next one.
+const PAUSE_BETWEEN_ROUNDS: f64 = 3.0;
+
+/// One round of the demo: a solvable board, the seed it was drawn from and
+/// the strategy to solve it with.
+pub struct DemoRound {
+    pub seed: u64,
+    pub board: Puzzle,
+    pub strategy: Strategy,
+}
+
+/// Kiosk mode. Once nobody has touched the app for `idle_timeout` seconds it
+/// keeps solving random boards with random strategies, until the next input.
+pub struct Demo {
+    idle_timeout: f64,
+    last_input_at: f64,
+    last_mouse: Vector2,
+    running: bool,
+    next_round_at: Option<f64>,
+}
+
+impl Demo {
+    pub fn new(idle_timeout: f64, r: &RaylibHandle) -> Self {
+        Demo {
+            idle_timeout,
+            last_input_at: r.get_time(),
+            last_mouse: r.get_mouse_position(),
+            running: false,
+            next_round_at: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Any key, click, scroll or mouse move stops the demo and restarts the
+    /// idle countdown.
+    pub fn read_input(&mut self, r: &mut RaylibHandle) {
+        let mouse = r.get_mouse_position();
+        let clicked = [
+            MouseButton::MOUSE_BUTTON_LEFT,
+            MouseButton::MOUSE_BUTTON_RIGHT,
+            MouseButton::MOUSE_BUTTON_MIDDLE,
+        ]
+        .into_iter()
+        .any(|button| r.is_mouse_button_pressed(button));
+        let touched = clicked
+            || r.get_key_pressed().is_some()
+            || r.get_mouse_wheel_move() != 0.0
+            || mouse != self.last_mouse;
+
+        self.last_mouse = mouse;
+        if touched {
+            self.last_input_at = r.get_time();
+            self.running = false;
+            self.next_round_at = None;
+        }
+    }
+
+    /// The next round once it is due. `round_finished` tells whether the
+    /// previous round is done playing back, a new round starts a short
+    /// pause after that.
+    pub fn next_round(
+        &mut self,
+        now: f64,
+        round_finished: bool,
+        goal: &Puzzle,
+    ) -> Option<DemoRound> {
+        if !self.running {
+            if now - self.last_input_at < self.idle_timeout {
+                return None;
+            }
+            println!("No input for {} s, starting the demo", self.idle_timeout);
+            self.running = true;
+            self.next_round_at = Some(now);
+        }
+
+        if !round_finished {
+            return None;
+        }
+        let due_at = *self.next_round_at.get_or_insert(now + PAUSE_BETWEEN_ROUNDS);
+        if now < due_at {
+            return None;
+        }
+        self.next_round_at = None;
+
+        let mut rng = rand::thread_rng();
+        let seed = rng.gen::<u64>();
+        let strategies = Strategy::all();
+        Some(DemoRound {
+            seed,
+            board: board_from_seed(seed, goal),
+            strategy: strategies[rng.gen_range(0..strategies.len())],
+        })
+    }
+}
@@ -1,38 +1,78 @@
+use nine::logic::Puzzle;
 use raylib::prelude::*;
 
-use crate::logic::Puzzle;
+use super::theme::Theme;
 
-pub fn map_color(num: &str) -> Color {
-    match num {
-        "1" => Color::DARKRED,
-        "2" => Color::DARKBLUE,
-        "3" => Color::DARKGREEN,
-        "4" => Color::DARKCYAN,
-        "5" => Color::DARKORANGE,
-        "6" => Color::DARKPURPLE,
-        "7" => Color::DARKBROWN,
-        "8" => Color::DARKGOLDENROD,
-        _ => Color::BLACK,
+pub fn map_color(theme: &Theme, num: &str) -> Color {
+    theme.tile_color(num.parse().unwrap_or(0))
+}
+
+/// Sizes of a board drawn by `draw_puzzle`: square tiles `tile` pixels wide,
+/// one every `stride` pixels, which leaves `stride - tile` between two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardSizes {
+    pub tile: i32,
+    pub stride: i32,
+}
+
+impl BoardSizes {
+    /// The boards of the top panel.
+    pub const MAIN: BoardSizes = BoardSizes {
+        tile: 25,
+        stride: 30,
+    };
+
+    /// Top left corner of cell `(i, j)` of a board drawn at `(x, y)`.
+    pub fn cell_origin(&self, x: i32, y: i32, (i, j): (usize, usize)) -> (i32, i32) {
+        (x + j as i32 * self.stride, y + i as i32 * self.stride)
+    }
+
+    /// Cell of a board drawn at `(x, y)` under the point `(px, py)`, if any.
+    pub fn cell_at(&self, x: i32, y: i32, (px, py): (i32, i32)) -> Option<(usize, usize)> {
+        let (dx, dy) = (px - x, py - y);
+        let side = 3 * self.stride;
+        if !(0..side).contains(&dx) || !(0..side).contains(&dy) {
+            return None;
+        }
+        Some(((dy / self.stride) as usize, (dx / self.stride) as usize))
     }
 }
 
-pub fn draw_sq_box(draw_handle: &mut RaylibDrawHandle, x: i32, y: i32, number: &str) {
-    draw_handle.draw_rectangle(x, y, 25, 25, map_color(number));
+/// A `size` pixels wide tile labeled `number`, the label scaled along.
+pub fn draw_sq_box(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    x: i32,
+    y: i32,
+    size: i32,
+    number: &str,
+) {
+    draw_handle.draw_rectangle(x, y, size, size, map_color(theme, number));
+    // Offsets and font as they look right on a 25 px tile.
+    let nudge = if number == "1" { 3 } else { 0 };
     draw_handle.draw_text(
         number,
-        x + 7 + (if number == "1" { 3 } else { 0 }),
-        y + 4,
-        20,
-        raylib::color::Color::WHITE,
+        x + (7 + nudge) * size / 25,
+        y + 4 * size / 25,
+        20 * size / 25,
+        theme.tile_text,
     );
 }
 
-pub fn draw_puzzle(draw_handle: &mut RaylibDrawHandle, puzzle: &Puzzle, x: i32, y: i32) {
+pub fn draw_puzzle(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    puzzle: &Puzzle,
+    x: i32,
+    y: i32,
+    sizes: BoardSizes,
+) {
     for i in 0..3 {
         for j in 0..3 {
             let s = format!("{}", puzzle.get_value(i, j));
             if s != "0" {
-                draw_sq_box(draw_handle, x + j as i32 * 30, y + i as i32 * 30, &s);
+                let (cell_x, cell_y) = sizes.cell_origin(x, y, (i, j));
+                draw_sq_box(draw_handle, theme, cell_x, cell_y, sizes.tile, &s);
             }
         }
     }
@@ -64,6 +104,7 @@ impl PuzzleCoord for SmallPuzzleCenter {
 
 pub fn draw_small_puzzle(
     draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
     puzzle: &Puzzle,
     coord: impl PuzzleCoord,
     with_border: Option<Color>,
@@ -79,7 +120,7 @@ pub fn draw_small_puzzle(
                     y + i as i32 * cell_size,
                     cell_size,
                     cell_size,
-                    map_color(&value.to_string()),
+                    theme.tile_color(value),
                 );
             }
         }
@@ -88,6 +129,324 @@ pub fn draw_small_puzzle(
     if let Some(color) = with_border {
         draw_handle.draw_rectangle_lines(x - 2, y - 2, 4 + cell_size * 3, 4 + cell_size * 3, color);
     } else {
-        draw_handle.draw_rectangle_lines(x - 1, y - 1, 2 + cell_size * 3, 2 + cell_size * 3, Color::BLACK);
+        draw_handle.draw_rectangle_lines(
+            x - 1,
+            y - 1,
+            2 + cell_size * 3,
+            2 + cell_size * 3,
+            theme.edge,
+        );
+    }
+}
+
+/// `initial` laid over `goal`, each cell outlined green where it already
+/// holds its goal tile and red where it does not, with the number of
+/// misplaced tiles below.
+pub fn draw_puzzle_diff(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    initial: &Puzzle,
+    goal: &Puzzle,
+    x: i32,
+    y: i32,
+) {
+    const CELL: i32 = 14;
+
+    for i in 0..3 {
+        for j in 0..3 {
+            let (cell_x, cell_y) = (x + j as i32 * CELL, y + i as i32 * CELL);
+            let value = initial.get_value(i, j);
+            let status = match value == goal.get_value(i, j) {
+                true => Color::GREEN,
+                false => Color::RED,
+            };
+
+            if value != 0 {
+                let number = value.to_string();
+                draw_handle.draw_rectangle(cell_x, cell_y, CELL, CELL, map_color(theme, &number));
+                draw_handle.draw_text(&number, cell_x + 4, cell_y + 2, 10, theme.tile_text);
+            }
+            draw_handle.draw_rectangle_lines(cell_x, cell_y, CELL, CELL, status);
+            draw_handle.draw_rectangle_lines(cell_x + 1, cell_y + 1, CELL - 2, CELL - 2, status);
+        }
     }
+
+    let misplaced = format!("{} off", initial.misplaced_tiles(goal, false));
+    draw_handle.draw_text(&misplaced, x, y + 3 * CELL + 4, 10, theme.text);
+}
+
+/// Small box next to the cursor describing one searched board.
+pub fn draw_node_info(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    x: i32,
+    y: i32,
+    (g, h): (i32, i32),
+    parent: &Puzzle,
+) {
+    let values = format!("g = {}, h = {}, f = {}", g, h, g + h);
+    let width = measure_text(&values, 10).max(measure_text("parent:", 10) + 30) + 10;
+    let (x, y) = (x + 12, y + 12);
+
+    draw_handle.draw_rectangle(x, y, width, 50, theme.panel);
+    draw_handle.draw_rectangle_lines(x, y, width, 50, theme.edge);
+    draw_handle.draw_text(&values, x + 5, y + 5, 10, theme.text);
+    draw_handle.draw_text("parent:", x + 5, y + 25, 10, theme.text);
+    draw_small_puzzle(
+        draw_handle,
+        theme,
+        parent,
+        SmallPuzzleCenter {
+            x: x + measure_text("parent:", 10) + 20,
+            y: y + 32,
+            cell_size: 5,
+        },
+        None,
+    );
+}
+
+/// Column listing frontier boards with their f-values, best first.
+pub fn draw_fringe_panel(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    x: i32,
+    y: i32,
+    entries: &[(Puzzle, f64)],
+) {
+    draw_handle.draw_rectangle(x, y, 100, 24 + entries.len() as i32 * 26, theme.panel);
+    draw_handle.draw_text("Frontier (by f)", x + 5, y + 5, 10, theme.text);
+
+    for (i, (puzzle, f)) in entries.iter().enumerate() {
+        let row_y = y + 24 + i as i32 * 26;
+        draw_small_puzzle(
+            draw_handle,
+            theme,
+            puzzle,
+            SmallPuzzleCenter {
+                x: x + 17,
+                y: row_y + 11,
+                cell_size: 6,
+            },
+            None,
+        );
+        draw_handle.draw_text(&format!("f = {}", f), x + 40, row_y + 6, 10, theme.text);
+    }
+}
+
+/// `goal` on a translucent box whose bottom right corner is `(right, bottom)`,
+/// as a fixed reference over the tree.
+pub fn draw_pinned_goal(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    goal: &Puzzle,
+    right: i32,
+    bottom: i32,
+) {
+    const CELL: i32 = 12;
+    const WIDTH: i32 = 3 * CELL + 20;
+    const HEIGHT: i32 = 3 * CELL + 32;
+
+    let (x, y) = (right - WIDTH, bottom - HEIGHT);
+    draw_handle.draw_rectangle(x, y, WIDTH, HEIGHT, theme.panel.fade(0.6));
+    draw_handle.draw_rectangle_lines(x, y, WIDTH, HEIGHT, theme.edge.fade(0.6));
+    draw_handle.draw_text("Goal", x + 5, y + 5, 10, theme.text);
+    draw_small_puzzle(
+        draw_handle,
+        theme,
+        goal,
+        SmallPuzzleCenter {
+            x: x + WIDTH / 2,
+            y: y + 22 + 3 * CELL / 2,
+            cell_size: CELL,
+        },
+        None,
+    );
+}
+
+/// Text on a filled, padded background sized to fit it.
+pub fn draw_badge(draw_handle: &mut RaylibDrawHandle, x: i32, y: i32, text: &str, color: Color) {
+    let width = measure_text(text, 10) + 8;
+    draw_handle.draw_rectangle(x, y, width, 16, color);
+    draw_handle.draw_text(text, x + 4, y + 3, 10, Color::WHITE);
+}
+
+/// Polyline through the cells the blank visits along `path`, over a board
+/// drawn by `draw_puzzle` at `(x, y)` with `sizes`.
+pub fn draw_blank_trace(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    path: &[Puzzle],
+    x: i32,
+    y: i32,
+    sizes: BoardSizes,
+) {
+    let centers = path
+        .iter()
+        .filter_map(|puzzle| puzzle.find_zero())
+        .map(|cell| {
+            let (cell_x, cell_y) = sizes.cell_origin(x, y, cell);
+            let half = sizes.tile / 2;
+            Vector2::new((cell_x + half) as f32, (cell_y + half) as f32)
+        })
+        .collect::<Vec<_>>();
+
+    for pair in centers.windows(2) {
+        draw_handle.draw_line_ex(pair[0], pair[1], 3.0, theme.accent);
+    }
+    if let Some(start) = centers.first() {
+        draw_handle.draw_circle_v(*start, 4.0, theme.accent);
+    }
+}
+
+/// Each tile's term of the Manhattan estimate towards `goal`, in the top
+/// right corner of its cell on a board drawn by `draw_puzzle` at `(x, y)`
+/// with `sizes`.
+pub fn draw_tile_distances(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    puzzle: &Puzzle,
+    goal: &Puzzle,
+    x: i32,
+    y: i32,
+    sizes: BoardSizes,
+) {
+    let distances = puzzle.manhattan_per_tile(goal);
+    for index in 0..9 {
+        let cell = Puzzle::index_to_coord(index);
+        let value = puzzle.get_value(cell.0, cell.1);
+        if value == 0 {
+            continue;
+        }
+
+        let text = distances[value as usize].to_string();
+        let (cell_x, cell_y) = sizes.cell_origin(x, y, cell);
+        let text_x = cell_x + sizes.tile - measure_text(&text, 10) - 1;
+        draw_handle.draw_text(&text, text_x, cell_y + 1, 10, theme.accent);
+    }
+}
+
+/// Outlines, over a board drawn by `draw_puzzle` at `(x, y)` with `sizes`,
+/// the tile that slid on the way from `before` to `after` and the cell it
+/// left, now the blank. Nothing is drawn unless the two are one move apart.
+pub fn draw_last_move(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    before: &Puzzle,
+    after: &Puzzle,
+    x: i32,
+    y: i32,
+    sizes: BoardSizes,
+) {
+    if before.direction_to(after).is_none() {
+        return;
+    }
+
+    for (cell, color) in [(before.blank(), theme.accent), (after.blank(), theme.edge)] {
+        let (cell_x, cell_y) = sizes.cell_origin(x, y, cell);
+        let bounds = Rectangle::new(
+            (cell_x - 2) as f32,
+            (cell_y - 2) as f32,
+            (sizes.tile + 4) as f32,
+            (sizes.tile + 4) as f32,
+        );
+        draw_handle.draw_rectangle_lines_ex(bounds, 2.0, color);
+    }
+}
+
+/// Hue of `share` along the g ramp, blue at 0 through green to yellow at 1.
+fn ramp_color(share: f32) -> Color {
+    Color::color_from_hsv(240.0 - 180.0 * share.clamp(0.0, 1.0), 0.8, 0.9)
+}
+
+/// Color of cost `g` on the ramp from 0 to `max_g`.
+pub fn g_ramp_color(g: i32, max_g: i32) -> Color {
+    match max_g {
+        0 => ramp_color(0.0),
+        _ => ramp_color(g as f32 / max_g as f32),
+    }
+}
+
+/// Colors of the parts of a combined heuristic, by source.
+pub const SOURCE_COLORS: [Color; 2] = [Color::SKYBLUE, Color::ORANGE];
+
+/// Color of `value` on the ramp from `min` to `max`.
+pub fn range_ramp_color(value: i32, (min, max): (i32, i32)) -> Color {
+    g_ramp_color(value - min, max - min)
+}
+
+/// Legend of `g_ramp_color`, a bar from g = 0 to `max_g` labeled at both
+/// ends.
+pub fn draw_g_legend(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    max_g: i32,
+    x: i32,
+    y: i32,
+) {
+    draw_ramp_legend(draw_handle, theme, "Edges by g", (0, max_g), x, y);
+}
+
+/// Legend of `range_ramp_color`, a bar from f = `min` to `max` labeled at
+/// both ends.
+pub fn draw_f_legend(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    range: (i32, i32),
+    x: i32,
+    y: i32,
+) {
+    draw_ramp_legend(draw_handle, theme, "Boards by f", range, x, y);
+}
+
+/// Legend of `SOURCE_COLORS`, a swatch and the name of every part of the
+/// combined heuristic.
+pub fn draw_source_legend(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    names: [&str; 2],
+    x: i32,
+    y: i32,
+) {
+    let width = names
+        .iter()
+        .map(|name| measure_text(name, 10) + 22)
+        .max()
+        .unwrap_or(0)
+        .max(110);
+
+    draw_handle.draw_rectangle(x, y, width, 38, theme.panel);
+    draw_handle.draw_text("Boards by term", x + 5, y + 3, 10, theme.text);
+    for (row, (name, color)) in names.iter().zip(SOURCE_COLORS).enumerate() {
+        let row_y = y + 15 + 11 * row as i32;
+        draw_handle.draw_rectangle(x + 5, row_y + 1, 8, 8, color);
+        draw_handle.draw_text(name, x + 17, row_y, 10, theme.text);
+    }
+}
+
+fn draw_ramp_legend(
+    draw_handle: &mut RaylibDrawHandle,
+    theme: &Theme,
+    title: &str,
+    (min, max): (i32, i32),
+    x: i32,
+    y: i32,
+) {
+    const BAR_WIDTH: i32 = 100;
+
+    draw_handle.draw_rectangle(x, y, BAR_WIDTH + 10, 38, theme.panel);
+    draw_handle.draw_text(title, x + 5, y + 3, 10, theme.text);
+    for i in 0..BAR_WIDTH {
+        let color = ramp_color(i as f32 / (BAR_WIDTH - 1) as f32);
+        draw_handle.draw_rectangle(x + 5 + i, y + 16, 1, 6, color);
+    }
+
+    let max_label = max.to_string();
+    draw_handle.draw_text(&min.to_string(), x + 5, y + 25, 10, theme.text);
+    draw_handle.draw_text(
+        &max_label,
+        x + 5 + BAR_WIDTH - measure_text(&max_label, 10),
+        y + 25,
+        10,
+        theme.text,
+    );
 }
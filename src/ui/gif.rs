@@ -1,3 +1,5 @@
+use std::fmt;
+
 use raylib::{texture::{Image, Texture2D}, RaylibHandle, RaylibThread};
 use rust_embed::Embed;
 
@@ -8,17 +10,71 @@ struct Asset;
 pub const ALICE_WIDTH: u32 = 640 / 4;
 pub const ALICE_HEIGHT: u32 = 650 / 4;
 
-pub fn load_alice(handle: &mut RaylibHandle, thread: &RaylibThread) -> Vec<Texture2D> {
+#[derive(Debug)]
+pub enum GifLoadError {
+    Image { frame: usize, message: String },
+    Texture { frame: usize, message: String },
+}
+
+impl fmt::Display for GifLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GifLoadError::Image { frame, message } => {
+                write!(f, "failed to decode frame {}: {}", frame, message)
+            }
+            GifLoadError::Texture { frame, message } => {
+                write!(f, "failed to upload frame {}: {}", frame, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GifLoadError {}
+
+/// Decodes frame `i` of the animation from its PNG bytes and scales it to
+/// the size it is drawn at.
+fn decode_frame(i: usize, data: &[u8]) -> Result<Image, GifLoadError> {
+    let mut frame = Image::load_image_from_mem(".png", data).map_err(|e| GifLoadError::Image {
+        frame: i,
+        message: e.to_string(),
+    })?;
+    frame.resize(ALICE_HEIGHT as i32, ALICE_WIDTH as i32);
+    Ok(frame)
+}
+
+pub fn load_alice(
+    handle: &mut RaylibHandle,
+    thread: &RaylibThread,
+) -> Result<Vec<Texture2D>, GifLoadError> {
     let mut frames = Vec::new();
     for i in 0.. {
         let frame = Asset::get(&*format!("{}.png", i));
         if let Some(frame) = frame {
-            let mut frame = Image::load_image_from_mem(".png", &frame.data).unwrap();
-            frame.resize(ALICE_HEIGHT as i32, ALICE_WIDTH as i32);
-            frames.push(handle.load_texture_from_image(thread, &frame).unwrap());
+            let frame = decode_frame(i, &frame.data)?;
+            let texture = handle
+                .load_texture_from_image(thread, &frame)
+                .map_err(|e| GifLoadError::Texture {
+                    frame: i,
+                    message: e.to_string(),
+                })?;
+            frames.push(texture);
         } else {
             break;
         }
     }
-    frames
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn garbage_frame_is_an_image_error() {
+        match decode_frame(0, b"not a png") {
+            Err(GifLoadError::Image { frame: 0, .. }) => {}
+            Err(error) => panic!("wrong error: {}", error),
+            Ok(_) => panic!("garbage decoded as a frame"),
+        }
+    }
 }
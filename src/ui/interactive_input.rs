@@ -1,8 +1,10 @@
+use nine::logic::Puzzle;
 use raylib::prelude::*;
 
-use crate::logic::Puzzle;
-
-use super::elements::draw_sq_box;
+use super::{
+    elements::{draw_sq_box, BoardSizes},
+    theme::Theme,
+};
 
 pub struct SetPuzzle {
     current: u8,
@@ -17,24 +19,35 @@ impl SetPuzzle {
         }
     }
 
-    pub fn draw(&self, draw_handle: &mut RaylibDrawHandle, x: i32, y: i32) {
+    pub fn draw(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        x: i32,
+        y: i32,
+        sizes: BoardSizes,
+    ) {
         for i in 0..3 {
             for j in 0..3 {
-                if i * 3 + j == self.current as usize {
+                let index = Puzzle::coord_to_index(i, j);
+                let (cell_x, cell_y) = sizes.cell_origin(x, y, (i, j));
+                if index == self.current as usize {
                     draw_handle.draw_rectangle_lines(
-                        x + j as i32 * 30,
-                        y + i as i32 * 30,
-                        25,
-                        25,
+                        cell_x,
+                        cell_y,
+                        sizes.tile,
+                        sizes.tile,
                         raylib::color::Color::RED,
                     );
                 } else {
-                    let content = self.content[i * 3 + j];
+                    let content = self.content[index];
                     if content > 0 && content < 9 {
                         draw_sq_box(
                             draw_handle,
-                            x + j as i32 * 30,
-                            y + i as i32 * 30,
+                            theme,
+                            cell_x,
+                            cell_y,
+                            sizes.tile,
                             &format!("{}", content),
                         );
                     }
@@ -78,13 +91,112 @@ impl SetPuzzle {
 
     pub fn get_puzzle(&self) -> Option<Puzzle> {
         if self.content.iter().all(|&x| x != 9) {
-            Some(Puzzle::new([
-                [self.content[0], self.content[1], self.content[2]],
-                [self.content[3], self.content[4], self.content[5]],
-                [self.content[6], self.content[7], self.content[8]],
-            ]))
+            let mut board = [[0; 3]; 3];
+            for (index, &value) in self.content.iter().enumerate() {
+                let (i, j) = Puzzle::index_to_coord(index);
+                board[i][j] = value;
+            }
+            Some(Puzzle::new(board))
         } else {
             None
         }
     }
 }
+
+/// Single-line text field, focused by clicking into it. Ctrl+V pastes.
+pub struct TextInput {
+    text: String,
+    focused: bool,
+    max_len: usize,
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        TextInput::with_max_len(24)
+    }
+}
+
+impl TextInput {
+    /// An empty field taking up to `max_len` characters.
+    pub fn with_max_len(max_len: usize) -> Self {
+        TextInput {
+            text: String::new(),
+            focused: false,
+            max_len,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn read_event(&mut self, r: &mut RaylibHandle, bounds: Rectangle) {
+        if r.is_mouse_button_pressed(raylib::consts::MouseButton::MOUSE_BUTTON_LEFT) {
+            self.focused = bounds.check_collision_point_rec(r.get_mouse_position());
+        }
+
+        if !self.focused {
+            return;
+        }
+
+        // Letters typed with Ctrl held are shortcuts, not text.
+        let ctrl = r.is_key_down(raylib::consts::KeyboardKey::KEY_LEFT_CONTROL)
+            || r.is_key_down(raylib::consts::KeyboardKey::KEY_RIGHT_CONTROL);
+        let mut typed = String::new();
+        while let Some(c) = r.get_char_pressed() {
+            if !ctrl {
+                typed.push(c);
+            }
+        }
+        if ctrl && r.is_key_pressed(raylib::consts::KeyboardKey::KEY_V) {
+            typed.push_str(&r.get_clipboard_text().unwrap_or_default());
+        }
+        for c in typed.chars() {
+            if !c.is_control() && self.text.chars().count() < self.max_len {
+                self.text.push(c);
+            }
+        }
+
+        if r.is_key_pressed(raylib::consts::KeyboardKey::KEY_BACKSPACE) {
+            self.text.pop();
+        } else if r.is_key_pressed(raylib::consts::KeyboardKey::KEY_ENTER) {
+            self.focused = false;
+        }
+    }
+
+    pub fn draw(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        bounds: Rectangle,
+        placeholder: &str,
+    ) {
+        let (x, y) = (bounds.x as i32, bounds.y as i32);
+        let (width, height) = (bounds.width as i32, bounds.height as i32);
+
+        draw_handle.draw_rectangle(x, y, width, height, theme.background);
+        let border = if self.focused {
+            theme.accent
+        } else {
+            theme.edge
+        };
+        draw_handle.draw_rectangle_lines(x, y, width, height, border);
+
+        if self.text.is_empty() && !self.focused {
+            draw_handle.draw_text(placeholder, x + 5, y + 6, 10, Color::GRAY);
+        } else {
+            let cursor = if self.focused { "_" } else { "" };
+            draw_handle.draw_text(
+                &format!("{}{}", self.text, cursor),
+                x + 5,
+                y + 6,
+                10,
+                theme.text,
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::ffi::CString;
+
+use nine::{
+    board_library::{BoardLibrary, LibraryEntry, LIBRARY_PATH},
+    logic::Puzzle,
+};
+use raylib::{prelude::*, rgui::RaylibDrawGui, rstr};
+
+use super::{interactive_input::TextInput, theme::Theme};
+
+const NAME_BOX: Rectangle = Rectangle {
+    x: 600.0,
+    y: 110.0,
+    width: 150.0,
+    height: 24.0,
+};
+
+const SAVE_BUTTON: Rectangle = Rectangle {
+    x: 760.0,
+    y: 110.0,
+    width: 60.0,
+    height: 24.0,
+};
+
+const ENTRY_LIST: Rectangle = Rectangle {
+    x: 600.0,
+    y: 140.0,
+    width: 150.0,
+    height: 24.0,
+};
+
+const LOAD_BUTTON: Rectangle = Rectangle {
+    x: 760.0,
+    y: 140.0,
+    width: 60.0,
+    height: 24.0,
+};
+
+/// Stand in for `SAVE_BUTTON` while an overwrite waits to be confirmed.
+const OVERWRITE_YES_BUTTON: Rectangle = Rectangle {
+    x: 760.0,
+    y: 110.0,
+    width: 28.0,
+    height: 24.0,
+};
+
+const OVERWRITE_NO_BUTTON: Rectangle = Rectangle {
+    x: 792.0,
+    y: 110.0,
+    width: 28.0,
+    height: 24.0,
+};
+
+/// Top panel controls to save the current boards under a name and to load
+/// saved ones back.
+pub struct LibraryPanel {
+    library: BoardLibrary,
+    name: TextInput,
+    selected: i32,
+    selecting: bool,
+    pending_overwrite: Option<LibraryEntry>,
+}
+
+impl LibraryPanel {
+    pub fn load() -> Self {
+        let library = BoardLibrary::load(LIBRARY_PATH).unwrap_or_else(|e| {
+            println!("Cannot load board library, starting empty: {}", e);
+            BoardLibrary::default()
+        });
+
+        LibraryPanel {
+            library,
+            name: TextInput::default(),
+            selected: 0,
+            selecting: false,
+            pending_overwrite: None,
+        }
+    }
+
+    /// Whether keyboard input currently goes to the name field.
+    pub fn is_typing(&self) -> bool {
+        self.name.is_focused()
+    }
+
+    fn store(&mut self, entry: LibraryEntry) {
+        self.library.insert(entry);
+        if let Err(e) = self.library.save(LIBRARY_PATH) {
+            println!("Cannot save board library: {}", e);
+        }
+    }
+
+    /// Draws the panel, returning the (initial, goal) pair the user loaded.
+    pub fn draw(
+        &mut self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        initial: &Puzzle,
+        goal: &Puzzle,
+    ) -> Option<(Puzzle, Puzzle)> {
+        let mut loaded = None;
+
+        if self.selecting {
+            draw_handle.gui_lock();
+        }
+
+        self.name.read_event(draw_handle, NAME_BOX);
+        self.name.draw(draw_handle, theme, NAME_BOX, "Board name");
+
+        if self.pending_overwrite.is_some() {
+            draw_handle.draw_text("Overwrite?", 760, 98, 10, theme.text);
+            if draw_handle.gui_button(OVERWRITE_YES_BUTTON, Some(rstr!("Yes"))) {
+                let entry = self.pending_overwrite.take().unwrap();
+                self.store(entry);
+            } else if draw_handle.gui_button(OVERWRITE_NO_BUTTON, Some(rstr!("No"))) {
+                self.pending_overwrite = None;
+            }
+        } else if draw_handle.gui_button(SAVE_BUTTON, Some(rstr!("Save")))
+            && !self.name.text().is_empty()
+        {
+            let entry = LibraryEntry {
+                name: self.name.text().to_string(),
+                initial: *initial,
+                goal: *goal,
+            };
+            match self.library.contains(&entry.name) {
+                true => self.pending_overwrite = Some(entry),
+                false => self.store(entry),
+            }
+        }
+
+        if draw_handle.gui_button(LOAD_BUTTON, Some(rstr!("Load"))) {
+            if let Some(entry) = self.library.entries.get(self.selected as usize) {
+                loaded = Some((entry.initial, entry.goal));
+            }
+        }
+
+        if self.selecting {
+            draw_handle.gui_unlock();
+        }
+
+        let names = match self.library.entries.is_empty() {
+            true => String::from("(empty)"),
+            false => self
+                .library
+                .names()
+                .map(|name| name.replace(';', ","))
+                .collect::<Vec<_>>()
+                .join(";"),
+        };
+        let names = CString::new(names).unwrap_or_default();
+        if self.selected as usize >= self.library.entries.len() {
+            self.selected = 0;
+        }
+        if draw_handle.gui_dropdown_box(
+            ENTRY_LIST,
+            Some(names.as_c_str()),
+            &mut self.selected,
+            self.selecting,
+        ) {
+            self.selecting = !self.selecting;
+        }
+
+        loaded
+    }
+}
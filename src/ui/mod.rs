@@ -1,3 +1,14 @@
+pub mod auto_play;
+pub mod demo;
 pub mod interactive_input;
 pub mod elements;
-pub mod gif;
\ No newline at end of file
+pub mod gif;
+pub mod library_panel;
+pub mod move_list;
+pub mod parity_explorer;
+pub mod play_mode;
+pub mod replay;
+pub mod report;
+pub mod share_panel;
+pub mod theme;
+pub mod tile_move;
@@ -0,0 +1,89 @@
+use nine::logic::Direction;
+use raylib::prelude::*;
+
+use super::theme::Theme;
+
+const WIDTH: i32 = 90;
+const ROW_HEIGHT: i32 = 14;
+/// Rows shown at once, the wheel scrolls through the rest.
+const ROWS: usize = 20;
+const HEIGHT: i32 = 24 + ROWS as i32 * ROW_HEIGHT;
+
+/// Scrollable list of the solution's moves, "1. Up", "2. Left", …, that
+/// keeps the move auto-play last made highlighted and in view.
+#[derive(Default)]
+pub struct MoveList {
+    /// Index of the first row shown.
+    scroll: usize,
+    /// The move last scrolled into view, the list only follows the
+    /// playback when it moves on so the wheel can look elsewhere meanwhile.
+    followed: Option<usize>,
+}
+
+impl MoveList {
+    /// Area the panel covers when drawn at `(x, y)`.
+    pub fn bounds(x: i32, y: i32) -> Rectangle {
+        Rectangle::new(x as f32, y as f32, WIDTH as f32, HEIGHT as f32)
+    }
+
+    /// Draws `moves` with move `current`, counted from 0, highlighted.
+    pub fn draw(
+        &mut self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        moves: &[Direction],
+        current: Option<usize>,
+        x: i32,
+        y: i32,
+    ) {
+        let last_scroll = moves.len().saturating_sub(ROWS);
+        if current != self.followed {
+            if let Some(current) = current {
+                if current < self.scroll {
+                    self.scroll = current;
+                } else if current >= self.scroll + ROWS {
+                    self.scroll = current + 1 - ROWS;
+                }
+            }
+            self.followed = current;
+        }
+        let wheel = draw_handle.get_mouse_wheel_move();
+        if wheel != 0.0
+            && MoveList::bounds(x, y).check_collision_point_rec(draw_handle.get_mouse_position())
+        {
+            let rows = wheel.abs().ceil() as usize;
+            self.scroll = match wheel > 0.0 {
+                true => self.scroll.saturating_sub(rows),
+                false => self.scroll + rows,
+            };
+        }
+        self.scroll = self.scroll.min(last_scroll);
+
+        draw_handle.draw_rectangle(x, y, WIDTH, HEIGHT, theme.panel);
+        draw_handle.draw_rectangle_lines(x, y, WIDTH, HEIGHT, theme.edge);
+        draw_handle.draw_text(
+            &format!("Moves ({})", moves.len()),
+            x + 5,
+            y + 5,
+            10,
+            theme.accent,
+        );
+
+        let shown = moves.iter().enumerate().skip(self.scroll).take(ROWS);
+        for (row, (index, direction)) in shown.enumerate() {
+            let row_y = y + 22 + row as i32 * ROW_HEIGHT;
+            let color = match Some(index) == current {
+                true => {
+                    draw_handle.draw_rectangle(x + 2, row_y, WIDTH - 4, ROW_HEIGHT, theme.accent);
+                    theme.tile_text
+                }
+                false => theme.text,
+            };
+            let label = format!("{}. {}", index + 1, direction.name());
+            draw_handle.draw_text(&label, x + 5, row_y + 2, 10, color);
+        }
+        if self.scroll < last_scroll {
+            draw_handle.draw_text("...", x + WIDTH - 15, y + HEIGHT - 12, 10, theme.text);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use nine::logic::Puzzle;
+use raylib::{prelude::*, rgui::RaylibDrawGui, rstr};
+
+use super::{
+    elements::{draw_small_puzzle, SmallPuzzleCenter},
+    theme::Theme,
+};
+
+const WIDTH: i32 = 300;
+const HEIGHT: i32 = 150;
+const CELL: i32 = 10;
+
+/// Random board that cannot reach `goal`.
+fn board_of_other_class(goal: &Puzzle) -> Puzzle {
+    let mut rng = rand::thread_rng();
+    loop {
+        let board = Puzzle::from_rng(&mut rng);
+        if !board.is_solvable_to(goal) {
+            break board;
+        }
+    }
+}
+
+/// The first two cells holding a tile, row by row.
+fn first_two_tiles(board: &Puzzle) -> ((usize, usize), (usize, usize)) {
+    let mut tiles = (0..9)
+        .map(Puzzle::index_to_coord)
+        .filter(|&(i, j)| board.get_value(i, j) != 0);
+    (tiles.next().unwrap(), tiles.next().unwrap())
+}
+
+fn class_name(board: &Puzzle) -> &'static str {
+    match board.parity() {
+        true => "odd",
+        false => "even",
+    }
+}
+
+/// Panel showing that the boards split into two parity classes: it draws a
+/// board from the class the goal is not in, which no search can solve, and
+/// swaps two of its tiles on request to move it over to the other class.
+pub struct ParityExplorer {
+    drawn: Puzzle,
+    swapped: bool,
+}
+
+impl ParityExplorer {
+    pub fn new(goal: &Puzzle) -> Self {
+        ParityExplorer {
+            drawn: board_of_other_class(goal),
+            swapped: false,
+        }
+    }
+
+    /// Area the panel covers when drawn at `(x, y)`.
+    pub fn bounds(x: i32, y: i32) -> Rectangle {
+        Rectangle::new(x as f32, y as f32, WIDTH as f32, HEIGHT as f32)
+    }
+
+    fn board(&self) -> Puzzle {
+        match self.swapped {
+            true => {
+                let (a, b) = first_two_tiles(&self.drawn);
+                self.drawn.swap_cells(a, b)
+            }
+            false => self.drawn,
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        goal: &Puzzle,
+        x: i32,
+        y: i32,
+    ) {
+        let board = self.board();
+        let solvable = board.is_solvable_to(goal);
+
+        draw_handle.draw_rectangle(x, y, WIDTH, HEIGHT, theme.panel);
+        draw_handle.draw_rectangle_lines(x, y, WIDTH, HEIGHT, theme.edge);
+        draw_handle.draw_text("Parity classes", x + 5, y + 5, 10, theme.accent);
+
+        let boards = [
+            (format!("Goal: {}", class_name(goal)), *goal, None),
+            (
+                format!("Board: {}", class_name(&board)),
+                board,
+                Some(match solvable {
+                    true => Color::GREEN,
+                    false => Color::RED,
+                }),
+            ),
+        ];
+        for (column, (label, puzzle, border)) in boards.into_iter().enumerate() {
+            let column_x = x + 20 + column as i32 * 140;
+            draw_handle.draw_text(&label, column_x, y + 22, 10, theme.text);
+            draw_small_puzzle(
+                draw_handle,
+                theme,
+                &puzzle,
+                SmallPuzzleCenter {
+                    x: column_x + 3 * CELL / 2,
+                    y: y + 40 + 3 * CELL / 2,
+                    cell_size: CELL,
+                },
+                border,
+            );
+        }
+
+        let verdict = match solvable {
+            true => "Same class as the goal: solvable",
+            false => "Other class: no path to the goal exists",
+        };
+        draw_handle.draw_text(verdict, x + 5, y + 80, 10, theme.text);
+        draw_handle.draw_text(
+            "Moves never change the class, swapping two tiles does.",
+            x + 5,
+            y + 94,
+            10,
+            theme.text,
+        );
+
+        let new_button = Rectangle::new((x + 5) as f32, (y + 116) as f32, 90.0, 24.0);
+        if draw_handle.gui_button(new_button, Some(rstr!("New board"))) {
+            *self = ParityExplorer::new(goal);
+        }
+
+        let swap_button = Rectangle::new((x + 100) as f32, (y + 116) as f32, 90.0, 24.0);
+        let swap_label = match self.swapped {
+            true => rstr!("Swap back"),
+            false => rstr!("Swap two tiles"),
+        };
+        if draw_handle.gui_button(swap_button, Some(swap_label)) {
+            self.swapped = !self.swapped;
+        }
+    }
+}
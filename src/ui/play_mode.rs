@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use nine::logic::{Puzzle, SolutionMap, SolutionMapBuilder};
+use raylib::prelude::*;
+
+use super::{
+    elements::BoardSizes,
+    tile_move::{notify_tile_move, TileMoveListener},
+};
+
+/// Seconds between two blank moves while walking the blank to a click.
+const STEP_INTERVAL: f64 = 0.15;
+/// Boards the oracle expands per frame while it is being built, a few
+/// milliseconds worth.
+const ORACLE_BOARDS_PER_FRAME: usize = 20_000;
+
+/// The `SolutionMap` of a goal, built a slice per frame so play can start
+/// right away.
+pub enum Oracle {
+    Building(SolutionMapBuilder),
+    Ready(SolutionMap),
+}
+
+impl Oracle {
+    pub fn new(goal: Puzzle) -> Self {
+        Oracle::Building(SolutionMapBuilder::new(goal))
+    }
+
+    pub fn goal(&self) -> &Puzzle {
+        match self {
+            Oracle::Building(builder) => builder.goal(),
+            Oracle::Ready(map) => map.goal(),
+        }
+    }
+
+    /// Builds on for one frame.
+    pub fn update(&mut self) {
+        if let Oracle::Building(builder) = self {
+            builder.step(ORACLE_BOARDS_PER_FRAME);
+            if builder.is_complete() {
+                let goal = *builder.goal();
+                let builder = std::mem::replace(builder, SolutionMapBuilder::new(goal));
+                *self = Oracle::Ready(builder.finish().expect("complete builder"));
+            }
+        }
+    }
+
+    /// The map once it is built.
+    pub fn map(&self) -> Option<&SolutionMap> {
+        match self {
+            Oracle::Building(_) => None,
+            Oracle::Ready(map) => Some(map),
+        }
+    }
+
+    /// Share of the map built so far, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        match self {
+            Oracle::Building(builder) => builder.progress(),
+            Oracle::Ready(_) => 1.0,
+        }
+    }
+}
+
+/// Whether the move from `before` to `after` is optimal, bringing the board
+/// one move closer to the goal of `oracle`, or a detour. `None` when the
+/// oracle cannot tell how far either is from its goal.
+fn is_optimal(oracle: &SolutionMap, before: &Puzzle, after: &Puzzle) -> Option<bool> {
+    Some(oracle.distance(after)? < oracle.distance(before)?)
+}
+
+/// Manual moves judged by the oracle: optimal ones bring the board one move
+/// closer to the goal, detours take it one further away. Every move does
+/// one or the other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MoveTally {
+    pub optimal: usize,
+    pub detours: usize,
+}
+
+impl MoveTally {
+    /// Counts the move from `before` to `after`, unless `oracle` cannot tell
+    /// how far either is from its goal.
+    pub fn record(&mut self, oracle: &SolutionMap, before: &Puzzle, after: &Puzzle) {
+        match is_optimal(oracle, before, after) {
+            Some(true) => self.optimal += 1,
+            Some(false) => self.detours += 1,
+            None => {}
+        }
+    }
+}
+
+/// Manual play on a board drawn by `draw_puzzle`. Clicking a cell walks the
+/// blank there one legal move at a time, so a tile next to the blank simply
+/// slides into it.
+pub struct PlayMode {
+    board: Puzzle,
+    route: VecDeque<(usize, usize)>,
+    next_step_at: f64,
+    moves: usize,
+    tally: MoveTally,
+}
+
+impl PlayMode {
+    pub fn new(board: Puzzle) -> Self {
+        PlayMode {
+            board,
+            route: VecDeque::new(),
+            next_step_at: 0.0,
+            moves: 0,
+            tally: MoveTally::default(),
+        }
+    }
+
+    pub fn board(&self) -> &Puzzle {
+        &self.board
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Moves judged so far, those made before the oracle was ready are not.
+    pub fn tally(&self) -> MoveTally {
+        self.tally
+    }
+
+    /// Starts walking the blank to the clicked cell of the board drawn at
+    /// `(x, y)` with `sizes`. Clicks outside the board or on the blank itself
+    /// are ignored.
+    pub fn read_event(&mut self, r: &RaylibHandle, x: i32, y: i32, sizes: BoardSizes) {
+        if !r.is_mouse_button_pressed(raylib::consts::MouseButton::MOUSE_BUTTON_LEFT) {
+            return;
+        }
+
+        let Some(target) = sizes.cell_at(x, y, (r.get_mouse_x(), r.get_mouse_y())) else {
+            return;
+        };
+        let blank = self.board.blank();
+        if target != blank {
+            self.route = blank_route(blank, target);
+            self.next_step_at = r.get_time();
+        }
+    }
+
+    /// Over the board drawn at `(x, y)` with `sizes`, an arrow from the tile
+    /// under the mouse to the blank next to it, green when sliding it is an
+    /// optimal move by `oracle` and red when it is a detour. Nothing is
+    /// drawn while the blank is still walking to an earlier click.
+    pub fn draw_hint(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        oracle: &SolutionMap,
+        x: i32,
+        y: i32,
+        sizes: BoardSizes,
+    ) {
+        if !self.route.is_empty() {
+            return;
+        }
+        let mouse = (draw_handle.get_mouse_x(), draw_handle.get_mouse_y());
+        let Some(tile) = sizes.cell_at(x, y, mouse) else {
+            return;
+        };
+        let Some(optimal) = self
+            .board
+            .swap_blank_with(tile.0, tile.1)
+            .and_then(|next| is_optimal(oracle, &self.board, &next))
+        else {
+            return;
+        };
+
+        let center = |cell| {
+            let (cell_x, cell_y) = sizes.cell_origin(x, y, cell);
+            let half = sizes.tile as f32 / 2.0;
+            (cell_x as f32 + half, cell_y as f32 + half)
+        };
+        let (from, to) = (center(tile), center(self.board.blank()));
+        // Unit vector of the slide, which is along a row or a column.
+        let (dx, dy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let color = match optimal {
+            true => Color::GREEN,
+            false => Color::RED,
+        };
+
+        let tip = Vector2::new(to.0 - dx * 6.0, to.1 - dy * 6.0);
+        let tail = Vector2::new(from.0 + dx * 6.0, from.1 + dy * 6.0);
+        draw_handle.draw_line_ex(tail, tip, 3.0, color);
+        for side in [-1.0, 1.0] {
+            let barb = Vector2::new(
+                tip.x - dx * 6.0 + dy * side * 5.0,
+                tip.y - dy * 6.0 - dx * side * 5.0,
+            );
+            draw_handle.draw_line_ex(barb, tip, 3.0, color);
+        }
+    }
+
+    /// Makes the next move of the route once its time has come, telling
+    /// `listener` which tile it slid and judging it with `oracle`, if there
+    /// is one yet.
+    pub fn update(
+        &mut self,
+        now: f64,
+        listener: &mut impl TileMoveListener,
+        oracle: Option<&SolutionMap>,
+    ) {
+        if now < self.next_step_at {
+            return;
+        }
+
+        if let Some((i, j)) = self.route.pop_front() {
+            match self.board.swap_blank_with(i, j) {
+                Some(next) => {
+                    notify_tile_move(listener, &self.board, &next);
+                    if let Some(oracle) = oracle {
+                        self.tally.record(oracle, &self.board, &next);
+                    }
+                    self.board = next;
+                    self.moves += 1;
+                    self.next_step_at = now + STEP_INTERVAL;
+                }
+                None => self.route.clear(),
+            }
+        }
+    }
+}
+
+/// Cells the blank passes through on a shortest walk from `from` to `to`,
+/// `from` excluded. Tiles never block the blank, so this is a BFS over the
+/// cells of an empty grid.
+fn blank_route(from: (usize, usize), to: (usize, usize)) -> VecDeque<(usize, usize)> {
+    let mut previous = [[None; 3]; 3];
+    let mut queue = VecDeque::from([from]);
+    previous[from.0][from.1] = Some(from);
+
+    while let Some((i, j)) = queue.pop_front() {
+        if (i, j) == to {
+            break;
+        }
+
+        let neighbours = [
+            (i.wrapping_sub(1), j),
+            (i + 1, j),
+            (i, j.wrapping_sub(1)),
+            (i, j + 1),
+        ];
+        for (next_i, next_j) in neighbours {
+            if next_i < 3 && next_j < 3 && previous[next_i][next_j].is_none() {
+                previous[next_i][next_j] = Some((i, j));
+                queue.push_back((next_i, next_j));
+            }
+        }
+    }
+
+    let mut route = VecDeque::new();
+    let mut current = to;
+    while current != from {
+        route.push_front(current);
+        current = previous[current.0][current.1].expect("every cell is reachable");
+    }
+    route
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use nine::{logic::Puzzle, search_dump::SearchDump};
+
+/// Plays back a saved search, revealing its expansions a few at a time.
+/// Stepping through it by hand pauses the playback.
+pub struct Replay {
+    dump: SearchDump,
+    shown: usize,
+    paused: bool,
+}
+
+impl Replay {
+    pub fn new(dump: SearchDump) -> Self {
+        Replay {
+            dump,
+            shown: 0,
+            paused: false,
+        }
+    }
+
+    /// A paused replay with every expansion of `dump` shown, to step back
+    /// from.
+    pub fn paused_at_end(dump: SearchDump) -> Self {
+        Replay {
+            shown: dump.expansions.len(),
+            dump,
+            paused: true,
+        }
+    }
+
+    pub fn initial(&self) -> Puzzle {
+        self.dump.initial
+    }
+
+    pub fn goal(&self) -> Puzzle {
+        self.dump.goal
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shown >= self.dump.expansions.len()
+    }
+
+    /// Whether the replay still advances on its own.
+    pub fn is_playing(&self) -> bool {
+        !self.paused && !self.is_finished()
+    }
+
+    /// Reveals the next `steps` expansions.
+    pub fn advance(&mut self, steps: usize) {
+        self.shown = (self.shown + steps).min(self.dump.expansions.len());
+    }
+
+    /// Hides the last `steps` expansions shown and pauses.
+    pub fn step_back(&mut self, steps: usize) {
+        self.shown = self.shown.saturating_sub(steps);
+        self.paused = true;
+    }
+
+    /// Reveals the next `steps` expansions and pauses.
+    pub fn step_forward(&mut self, steps: usize) {
+        self.advance(steps);
+        self.paused = true;
+    }
+
+    /// The closed set as it was after the expansions shown so far.
+    pub fn map(&self) -> HashMap<Puzzle, (Puzzle, i32)> {
+        self.dump.map_after(self.shown)
+    }
+
+    /// Boards expanded so far, in order.
+    pub fn order(&self) -> Vec<Puzzle> {
+        self.dump.expansions[..self.shown]
+            .iter()
+            .map(|entry| entry.puzzle)
+            .collect()
+    }
+
+    pub fn status(&self) -> String {
+        format!(
+            "Replay: {} / {} expansions{}",
+            self.shown,
+            self.dump.expansions.len(),
+            match self.paused {
+                true => ", paused",
+                false => "",
+            }
+        )
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use nine::logic::{
+    solve_from_initials, AStarHeuristic1, AStarHeuristic2, BfsHeuristic, Heuristic,
+    ManhattanHeuristic, Puzzle, SearchTree,
+};
+use raylib::prelude::*;
+
+use super::theme::Theme;
+
+/// Outcome of one strategy on the compared board.
+struct ReportRow {
+    name: &'static str,
+    expanded: usize,
+    steps: Option<usize>,
+    elapsed: Duration,
+}
+
+struct ClosedSet {
+    goal: Puzzle,
+    map: HashMap<Puzzle, (Puzzle, i32)>,
+}
+
+impl SearchTree for ClosedSet {
+    fn goal_reached(&self) -> bool {
+        self.map.contains_key(&self.goal)
+    }
+    fn get(&self, key: &Puzzle) -> Option<(Puzzle, i32)> {
+        self.map.get(key).copied()
+    }
+    fn set(&mut self, key: Puzzle, value: (Puzzle, i32)) {
+        self.map.insert(key, value);
+    }
+}
+
+fn run<H: Heuristic>(initial: Puzzle, goal: Puzzle) -> ReportRow {
+    let start = Instant::now();
+    let mut closed_set = ClosedSet {
+        goal,
+        map: HashMap::new(),
+    };
+    let solution = solve_from_initials::<_, H>(&[initial], goal, &mut closed_set);
+
+    ReportRow {
+        name: H::new().name(),
+        expanded: closed_set.map.len(),
+        steps: solution.map(|(_, path)| path.len() - 1),
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Every strategy run on the same boards in a background thread, filled in
+/// row by row as each one finishes. BFS goes first and gives the shortest
+/// path length the others are compared to.
+pub struct Report {
+    rows: Vec<ReportRow>,
+    receiver: Option<Receiver<ReportRow>>,
+}
+
+impl Report {
+    pub fn start(initial: Puzzle, goal: Puzzle) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let runs: [fn(Puzzle, Puzzle) -> ReportRow; 4] = [
+                run::<BfsHeuristic>,
+                run::<AStarHeuristic1>,
+                run::<AStarHeuristic2>,
+                run::<ManhattanHeuristic>,
+            ];
+            for run in runs {
+                if sender.send(run(initial, goal)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Report {
+            rows: Vec::new(),
+            receiver: Some(receiver),
+        }
+    }
+
+    /// Collects the rows finished since the last call.
+    pub fn poll(&mut self) {
+        if let Some(receiver) = &self.receiver {
+            loop {
+                match receiver.try_recv() {
+                    Ok(row) => self.rows.push(row),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.receiver = None;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, draw_handle: &mut RaylibDrawHandle, theme: &Theme, x: i32, y: i32) {
+        const COLUMNS: [i32; 5] = [0, 90, 170, 220, 290];
+        let shortest = self.rows.first().and_then(|row| row.steps);
+
+        let rows = self.rows.len() as i32 + self.receiver.is_some() as i32;
+        draw_handle.draw_rectangle(x, y, 360, 26 + rows * 16, theme.panel);
+
+        let header = ["Strategy", "Expanded", "Steps", "Time", "Optimal"];
+        for (column, text) in COLUMNS.iter().zip(header) {
+            draw_handle.draw_text(text, x + 5 + column, y + 5, 10, theme.accent);
+        }
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let row_y = y + 21 + i as i32 * 16;
+            let optimal = match (row.steps, shortest) {
+                (Some(steps), Some(shortest)) if steps == shortest => String::from("yes"),
+                (Some(steps), Some(shortest)) => format!("no (+{})", steps - shortest),
+                _ => String::from("-"),
+            };
+            let cells = [
+                row.name.to_string(),
+                row.expanded.to_string(),
+                row.steps
+                    .map_or(String::from("-"), |steps| steps.to_string()),
+                format!("{} ms", row.elapsed.as_millis()),
+                optimal,
+            ];
+            for (column, text) in COLUMNS.iter().zip(cells) {
+                draw_handle.draw_text(&text, x + 5 + column, row_y, 10, theme.text);
+            }
+        }
+
+        if self.receiver.is_some() {
+            let row_y = y + 21 + self.rows.len() as i32 * 16;
+            draw_handle.draw_text("Solving...", x + 5, row_y, 10, theme.text);
+        }
+    }
+}
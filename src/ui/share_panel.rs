@@ -0,0 +1,90 @@
+use nine::logic::SharedProblem;
+use raylib::{prelude::*, rgui::RaylibDrawGui, rstr};
+
+use super::{interactive_input::TextInput, theme::Theme};
+
+const WIDTH: i32 = 290;
+const HEIGHT: i32 = 96;
+/// Longer than the code of any solution a 3x3 board needs.
+const MAX_CODE_LEN: usize = 64;
+
+/// Copies the problem and solution on screen as a share code, and loads one
+/// pasted back in.
+pub struct SharePanel {
+    code: TextInput,
+    message: Option<String>,
+}
+
+impl Default for SharePanel {
+    fn default() -> Self {
+        SharePanel {
+            code: TextInput::with_max_len(MAX_CODE_LEN),
+            message: None,
+        }
+    }
+}
+
+impl SharePanel {
+    /// Area the panel covers when drawn at `(x, y)`.
+    pub fn bounds(x: i32, y: i32) -> Rectangle {
+        Rectangle::new(x as f32, y as f32, WIDTH as f32, HEIGHT as f32)
+    }
+
+    /// Whether keyboard input currently goes to the code field.
+    pub fn is_typing(&self) -> bool {
+        self.code.is_focused()
+    }
+
+    /// Draws the panel, `shared` being what is on screen, if anything is
+    /// solved. Returns the problem of the code the user loaded.
+    pub fn draw(
+        &mut self,
+        draw_handle: &mut RaylibDrawHandle,
+        theme: &Theme,
+        shared: Option<&SharedProblem>,
+        x: i32,
+        y: i32,
+    ) -> Option<SharedProblem> {
+        let mut loaded = None;
+
+        draw_handle.draw_rectangle(x, y, WIDTH, HEIGHT, theme.panel);
+        draw_handle.draw_rectangle_lines(x, y, WIDTH, HEIGHT, theme.edge);
+        draw_handle.draw_text("Share code", x + 5, y + 5, 10, theme.accent);
+
+        let copy_button = Rectangle::new((x + 5) as f32, (y + 20) as f32, 110.0, 24.0);
+        if draw_handle.gui_button(copy_button, Some(rstr!("Copy share code"))) {
+            self.message = Some(match shared {
+                Some(shared) => {
+                    let code = shared.encode();
+                    match draw_handle.set_clipboard_text(&code) {
+                        Ok(()) => format!("Copied {}", code),
+                        Err(e) => format!("Cannot copy: {}", e),
+                    }
+                }
+                None => "Nothing solved to share yet".to_string(),
+            });
+        }
+
+        let code_box = Rectangle::new((x + 5) as f32, (y + 50) as f32, 200.0, 24.0);
+        self.code.read_event(draw_handle, code_box);
+        self.code
+            .draw(draw_handle, theme, code_box, "Paste a share code");
+
+        let load_button = Rectangle::new((x + 210) as f32, (y + 50) as f32, 75.0, 24.0);
+        if draw_handle.gui_button(load_button, Some(rstr!("Load"))) {
+            match SharedProblem::decode(self.code.text()) {
+                Ok(shared) => {
+                    self.message = Some(format!("Loaded {} moves", shared.moves.len()));
+                    loaded = Some(shared);
+                }
+                Err(e) => self.message = Some(format!("Cannot load: {}", e)),
+            }
+        }
+
+        if let Some(message) = &self.message {
+            draw_handle.draw_text(message, x + 5, y + 80, 10, theme.text);
+        }
+
+        loaded
+    }
+}
@@ -0,0 +1,96 @@
+use raylib::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub dark: bool,
+    pub background: Color,
+    pub panel: Color,
+    pub text: Color,
+    pub accent: Color,
+    pub edge: Color,
+    /// Boards and edges on the solution path in the tree.
+    pub path: Color,
+    pub tile_text: Color,
+    pub tiles: [Color; 8],
+}
+
+impl Theme {
+    pub const LIGHT: Theme = Theme {
+        dark: false,
+        background: Color::WHITE,
+        panel: Color::RAYWHITE,
+        text: Color::BLACK,
+        accent: Color::DARKCYAN,
+        edge: Color::BLACK,
+        path: Color::RED,
+        tile_text: Color::WHITE,
+        tiles: [
+            Color::DARKRED,
+            Color::DARKBLUE,
+            Color::DARKGREEN,
+            Color::DARKCYAN,
+            Color::DARKORANGE,
+            Color::DARKPURPLE,
+            Color::DARKBROWN,
+            Color::DARKGOLDENROD,
+        ],
+    };
+
+    pub const DARK: Theme = Theme {
+        dark: true,
+        background: Color::new(24, 24, 24, 255),
+        panel: Color::new(40, 40, 40, 255),
+        text: Color::RAYWHITE,
+        accent: Color::SKYBLUE,
+        edge: Color::LIGHTGRAY,
+        path: Color::RED,
+        tile_text: Color::BLACK,
+        tiles: [
+            Color::new(255, 110, 110, 255),
+            Color::new(110, 160, 255, 255),
+            Color::new(110, 220, 130, 255),
+            Color::new(100, 220, 220, 255),
+            Color::new(255, 170, 80, 255),
+            Color::new(200, 140, 255, 255),
+            Color::new(210, 170, 130, 255),
+            Color::new(240, 210, 90, 255),
+        ],
+    };
+
+    pub fn toggled(&self) -> Theme {
+        match self.dark {
+            true => Theme::LIGHT,
+            false => Theme::DARK,
+        }
+    }
+
+    pub fn tile_color(&self, value: u8) -> Color {
+        match value {
+            1..=8 => self.tiles[value as usize - 1],
+            _ => self.text,
+        }
+    }
+
+    /// Switches the raygui controls to match this theme.
+    pub fn apply_gui_style(&self, handle: &mut RaylibHandle) {
+        handle.gui_load_style_default();
+        if !self.dark {
+            return;
+        }
+
+        for (property, color) in [
+            (GuiControlProperty::BORDER_COLOR_NORMAL, self.edge),
+            (GuiControlProperty::BASE_COLOR_NORMAL, self.panel),
+            (GuiControlProperty::TEXT_COLOR_NORMAL, self.text),
+            (GuiControlProperty::BORDER_COLOR_FOCUSED, self.accent),
+            (GuiControlProperty::BASE_COLOR_FOCUSED, self.background),
+            (GuiControlProperty::TEXT_COLOR_FOCUSED, self.accent),
+        ] {
+            handle.gui_set_style(GuiControl::DEFAULT, property as i32, color_to_int(color));
+        }
+    }
+}
+
+fn color_to_int(color: Color) -> i32 {
+    ((color.r as i32) << 24) | ((color.g as i32) << 16) | ((color.b as i32) << 8) | color.a as i32
+}
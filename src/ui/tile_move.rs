@@ -0,0 +1,64 @@
+use nine::logic::Puzzle;
+use raylib::prelude::*;
+
+use super::elements::BoardSizes;
+
+/// Seconds the flash on a moved tile takes to fade out.
+const FLASH_SECONDS: f64 = 0.3;
+
+/// Told about every tile sliding on the main board, in play mode and
+/// auto-play alike, so sound or effects can follow the moves without either
+/// of them knowing.
+pub trait TileMoveListener {
+    /// `tile` slid from cell `from` into cell `to`, where the blank was.
+    fn on_tile_move(&mut self, _tile: u8, _from: (usize, usize), _to: (usize, usize)) {}
+}
+
+/// Reports the tile that moved between `before` and `after`, one blank move
+/// apart, to `listener`.
+pub fn notify_tile_move(listener: &mut impl TileMoveListener, before: &Puzzle, after: &Puzzle) {
+    let (from, to) = (after.blank(), before.blank());
+    listener.on_tile_move(before.get_value(from.0, from.1), from, to);
+}
+
+/// Briefly lights up the cell a tile slid into.
+#[derive(Default)]
+pub struct TileFlash {
+    cell: Option<(usize, usize)>,
+    /// When the flash was first drawn, it fades out from there.
+    started_at: Option<f64>,
+}
+
+impl TileMoveListener for TileFlash {
+    fn on_tile_move(&mut self, _tile: u8, _from: (usize, usize), to: (usize, usize)) {
+        self.cell = Some(to);
+        self.started_at = None;
+    }
+}
+
+impl TileFlash {
+    /// Draws the flash over a board drawn by `draw_puzzle` at `(x, y)` with
+    /// `sizes`.
+    pub fn draw(&mut self, draw_handle: &mut RaylibDrawHandle, x: i32, y: i32, sizes: BoardSizes) {
+        let Some(cell) = self.cell else {
+            return;
+        };
+
+        let now = draw_handle.get_time();
+        let age = now - *self.started_at.get_or_insert(now);
+        if age >= FLASH_SECONDS {
+            self.cell = None;
+            return;
+        }
+
+        let alpha = 0.8 * (1.0 - age / FLASH_SECONDS) as f32;
+        let (cell_x, cell_y) = sizes.cell_origin(x, y, cell);
+        draw_handle.draw_rectangle(
+            cell_x,
+            cell_y,
+            sizes.tile,
+            sizes.tile,
+            Color::WHITE.fade(alpha),
+        );
+    }
+}